@@ -1,6 +1,13 @@
 use crate::prelude::*;
 
-use crate::{ios_deploy::detect::DetectDevicesConfig, open::well_known::WellKnown, xcrun::simctl};
+use crate::{
+	ios_deploy::{
+		detect::{DetectDevicesConfig, DeviceFilter},
+		upload::UploadConfig,
+	},
+	open::well_known::WellKnown,
+	xcrun::simctl,
+};
 
 pub mod run;
 
@@ -116,6 +123,128 @@ pub enum IosDeploy {
 
 		#[clap(flatten)]
 		auto_detect_config: DetectDevicesConfig,
+
+		#[clap(flatten)]
+		device_filter: DeviceFilter,
+
+		#[clap(flatten)]
+		config: UploadConfig,
+	},
+	/// Lists, checks for or uninstalls apps on a real device
+	#[clap(subcommand)]
+	Apps(IosDeployApps),
+	/// Manages the files in an app's data container on a real device
+	#[clap(subcommand)]
+	Files(IosDeployFiles),
+	/// Prints real devices as they connect or disconnect, until interrupted.
+	/// Each detection waits --timeout seconds. With --json, each event is printed as a line of JSON
+	Watch {
+		#[clap(flatten)]
+		config: DetectDevicesConfig,
+	},
+}
+
+/// Detects devices, then selects one with a [DeviceFilter]
+#[derive(Args, Debug)]
+pub struct PhysicalDeviceArgs {
+	#[clap(flatten)]
+	pub detect_config: DetectDevicesConfig,
+
+	#[clap(flatten)]
+	pub filter: DeviceFilter,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum IosDeployApps {
+	/// Lists the bundle identifiers of installed apps
+	List {
+		#[clap(flatten)]
+		device: PhysicalDeviceArgs,
+	},
+	/// Checks whether an app is installed
+	Exists {
+		#[clap(flatten)]
+		device: PhysicalDeviceArgs,
+
+		#[arg(long)]
+		bundle_id: String,
+	},
+	/// Uninstalls an app
+	Uninstall {
+		#[clap(flatten)]
+		device: PhysicalDeviceArgs,
+
+		#[arg(long)]
+		bundle_id: String,
+	},
+}
+
+#[derive(Subcommand, Debug)]
+pub enum IosDeployFiles {
+	/// Lists the files in an app's data container
+	List {
+		#[clap(flatten)]
+		device: PhysicalDeviceArgs,
+
+		#[arg(long)]
+		bundle_id: String,
+
+		/// Only lists files under this directory, e.g. "/Documents"
+		#[arg(long)]
+		path: Option<String>,
+	},
+	/// Downloads an app's data container, or a path within it
+	Download {
+		#[clap(flatten)]
+		device: PhysicalDeviceArgs,
+
+		#[arg(long)]
+		bundle_id: String,
+
+		/// Only downloads this path, e.g. "/Library/Application Support/db.sqlite"
+		#[arg(long)]
+		path: Option<String>,
+
+		/// The local directory to download into
+		#[arg(long)]
+		output: Utf8PathBuf,
+	},
+	/// Uploads a local file into an app's data container
+	Upload {
+		#[clap(flatten)]
+		device: PhysicalDeviceArgs,
+
+		#[arg(long)]
+		bundle_id: String,
+
+		#[arg(long)]
+		file: Utf8PathBuf,
+
+		/// Destination in the data container, e.g. "/Documents/fixture.json"
+		#[arg(long)]
+		path: String,
+	},
+	/// Creates a directory in an app's data container
+	Mkdir {
+		#[clap(flatten)]
+		device: PhysicalDeviceArgs,
+
+		#[arg(long)]
+		bundle_id: String,
+
+		#[arg(long)]
+		path: String,
+	},
+	/// Removes a file or empty directory from an app's data container
+	Rm {
+		#[clap(flatten)]
+		device: PhysicalDeviceArgs,
+
+		#[arg(long)]
+		bundle_id: String,
+
+		#[arg(long)]
+		path: String,
 	},
 }
 
@@ -154,8 +283,18 @@ pub enum Spctl {
 
 #[derive(Subcommand, Debug)]
 pub enum XcRun {
-	#[clap(subcommand)]
-	Simctl(Simctl),
+	Simctl(Box<SimctlArgs>),
+}
+
+#[derive(Args, Debug)]
+pub struct SimctlArgs {
+	/// Operates on the device set at this path instead of the default simulators,
+	/// e.g. to isolate parallel CI jobs
+	#[arg(long, global = true)]
+	pub device_set: Option<Utf8PathBuf>,
+
+	#[command(subcommand)]
+	pub command: Simctl,
 }
 
 #[derive(Debug, ValueEnum, Clone)]
@@ -184,6 +323,415 @@ pub enum Simctl {
 		#[clap(flatten)]
 		args: simctl::launch::CliLaunchArgs,
 	},
+	/// Opens a URL in a booted simulator, e.g. a universal link or custom URL scheme
+	OpenUrl {
+		/// The URL to open, e.g. "myapp://some/deep/link".
+		/// Comes before the optional simulator name, e.g. `open-url myapp://link "iPhone 15"`
+		url: String,
+
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+	},
+	/// Sends a simulated push notification to an app on a booted simulator
+	Push {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		#[clap(flatten)]
+		bundle_id: BundleIdentifierArgs,
+
+		#[clap(flatten)]
+		args: simctl::push::CliPushArgs,
+	},
+	/// Manages an app's privacy permissions on a booted simulator
+	#[clap(subcommand)]
+	Privacy(Privacy),
+	/// Simulates the location of a booted simulator
+	#[clap(subcommand)]
+	Location(Location),
+	/// Overrides the status bar of a booted simulator, e.g. for screenshots
+	#[clap(subcommand)]
+	StatusBar(StatusBar),
+	/// Gets or sets UI and accessibility settings of a booted simulator
+	#[clap(subcommand)]
+	Ui(Ui),
+	/// Copies text to the pasteboard of a booted simulator
+	Pbcopy {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// The text to copy. Read from stdin if not provided
+		#[arg(long)]
+		text: Option<String>,
+	},
+	/// Prints the pasteboard contents of a booted simulator
+	Pbpaste {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+	},
+	/// Adds photos, videos or contacts (.vcf) to a booted simulator
+	AddMedia {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// Can be passed multiple times
+		#[arg(long, required = true)]
+		file: Vec<Utf8PathBuf>,
+	},
+	/// Manages certificates in the keychain of a booted simulator
+	#[clap(subcommand)]
+	Keychain(Keychain),
+	/// Collects logs, crash reports, `simctl diagnose` and tool versions into one archive,
+	/// for debugging a broken simulator
+	Diagnostics {
+		/// Where to write the .tar.gz archive
+		#[arg(long, default_value = "simctl-diagnostics.tar.gz")]
+		output: Utf8PathBuf,
+
+		/// Includes the logs of the simulator with this name, e.g. "iPhone 15 Pro"
+		#[arg(long)]
+		device: Option<String>,
+
+		/// Includes crash reports mentioning this bundle identifier
+		#[arg(long)]
+		bundle_id: Option<String>,
+
+		/// Skips `simctl diagnose`, which is thorough but can take minutes
+		#[arg(long)]
+		no_simctl_diagnose: bool,
+	},
+	/// Manages installed simulator runtimes
+	#[clap(subcommand)]
+	Runtime(Runtime),
+	/// Prints simulator changes (created, deleted, booted, shut down, became unavailable)
+	/// as they happen, until interrupted. With --json, each event is printed as a line of JSON
+	Watch {
+		/// Seconds between polls of `simctl list`
+		#[arg(long, default_value = "1", value_parser = parse_seconds)]
+		interval: std::time::Duration,
+	},
+	/// Creates the simulators described in a TOML file that don't exist yet
+	Ensure {
+		/// A TOML file with `[[simulator]]` tables, each with a `device`,
+		/// and optionally a `runtime` (e.g. "iOS 17.4", default "latest") and `name`
+		#[arg(long)]
+		file: Utf8PathBuf,
+
+		/// Also deletes every simulator not described in the file
+		#[arg(long)]
+		delete_unlisted: bool,
+
+		/// Only prints what would be created and deleted
+		#[arg(long)]
+		dry_run: bool,
+	},
+	/// Runs an executable inside a booted simulator, e.g.
+	/// `apple-clis xcrun simctl spawn --booted -- /path/to/harness --flag`
+	Spawn {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// Sets an environment variable in the spawned process, as KEY=VALUE.
+		/// Can be passed multiple times
+		#[arg(long, value_parser = parse_env_var)]
+		env: Vec<(String, String)>,
+
+		/// The executable followed by its arguments
+		#[arg(last = true, required = true)]
+		command: Vec<String>,
+	},
+	/// Prints an environment variable of a booted simulator
+	Getenv {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// The environment variable to print, e.g. "HOME"
+		#[arg(long = "var", id = "var_name", value_name = "VAR")]
+		var_name: String,
+	},
+	/// Shows recent log events from a booted simulator, or streams them with --follow
+	Logs {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// Streams new events as they are logged, until interrupted.
+		/// With --json, each event is printed as a line of JSON
+		#[arg(long)]
+		follow: bool,
+
+		/// Only events from this `os_log` subsystem, e.g. "com.example.app"
+		#[arg(long)]
+		subsystem: Option<String>,
+
+		/// Only events from this `os_log` category
+		#[arg(long)]
+		category: Option<String>,
+
+		/// Only events from the process with this executable name
+		#[arg(long)]
+		process: Option<String>,
+
+		/// The most verbose level to include
+		#[arg(long, value_enum, default_value = "debug")]
+		level: simctl::log::LogLevel,
+
+		/// How far back to show events from, without --follow. E.g. "5m", "2h" or "1d"
+		#[arg(long, default_value = "5m", conflicts_with = "follow")]
+		last: String,
+	},
+	/// Takes a screenshot of a booted simulator
+	Screenshot {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// Where to save the screenshot, e.g. "screenshot.png"
+		#[arg(long)]
+		output: Utf8PathBuf,
+
+		#[clap(flatten)]
+		options: simctl::io::ScreenshotOptions,
+	},
+	/// Records a video of a booted simulator
+	Record {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// Where to save the video, e.g. "recording.mov"
+		#[arg(long)]
+		output: Utf8PathBuf,
+
+		/// Stops recording after this many seconds.
+		/// If not provided, recording stops when Enter is pressed
+		#[arg(long, value_parser = parse_seconds)]
+		duration: Option<std::time::Duration>,
+
+		#[clap(flatten)]
+		options: simctl::io::RecordVideoOptions,
+	},
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Privacy {
+	/// Grants an app access to a service, without prompting
+	Grant {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		#[arg(long, value_enum)]
+		service: simctl::privacy::PrivacyService,
+
+		#[clap(flatten)]
+		bundle_id: BundleIdentifierArgs,
+	},
+	/// Revokes an app's access to a service
+	Revoke {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		#[arg(long, value_enum)]
+		service: simctl::privacy::PrivacyService,
+
+		#[clap(flatten)]
+		bundle_id: BundleIdentifierArgs,
+	},
+	/// Resets access to a service, so the app will prompt again.
+	/// Resets every app if no bundle identifier is given
+	Reset {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		#[arg(long, value_enum)]
+		service: simctl::privacy::PrivacyService,
+
+		#[arg(long)]
+		bundle_id: Option<String>,
+	},
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Location {
+	/// Sets a fixed location
+	Set {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// E.g. "-33.8568,151.2153"
+		#[arg(long, allow_hyphen_values = true)]
+		coordinate: simctl::location::Coordinate,
+	},
+	/// Stops any running route, and clears the simulated location
+	Clear {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+	},
+	/// Travels along a route of waypoints in the background
+	Route {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		#[clap(flatten)]
+		waypoints: LocationWaypointsArgs,
+
+		/// In meters per second
+		#[arg(long)]
+		speed: Option<f64>,
+
+		/// Emit a location update every this many meters
+		#[arg(long, conflicts_with = "interval")]
+		distance: Option<f64>,
+
+		/// Emit a location update every this many seconds
+		#[arg(long)]
+		interval: Option<f64>,
+	},
+}
+
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct LocationWaypointsArgs {
+	/// Waypoints to travel between, e.g. --waypoint=-33.8568,151.2153 --waypoint=-33.8688,151.2093
+	#[arg(long = "waypoint", allow_hyphen_values = true)]
+	pub waypoints: Vec<simctl::location::Coordinate>,
+
+	/// A GPX file to read the track, route or waypoints from
+	#[arg(long)]
+	pub gpx: Option<Utf8PathBuf>,
+}
+
+pub(crate) fn parse_env_var(arg: &str) -> std::result::Result<(String, String), String> {
+	arg
+		.split_once('=')
+		.map(|(key, value)| (key.to_owned(), value.to_owned()))
+		.ok_or_else(|| format!("expected KEY=VALUE, found {:?}", arg))
+}
+
+/// Parses a non-negative, finite number of seconds, e.g. "0.5"
+pub(crate) fn parse_seconds(arg: &str) -> std::result::Result<std::time::Duration, String> {
+	let seconds: f64 = arg
+		.parse()
+		.map_err(|_| format!("expected a number of seconds, found {:?}", arg))?;
+	std::time::Duration::try_from_secs_f64(seconds).map_err(|_| {
+		format!(
+			"expected a non-negative, finite number of seconds, found {:?}",
+			arg
+		)
+	})
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Runtime {
+	/// Lists installed runtime images and their disk usage
+	List,
+	/// Installs a runtime from a disk image, e.g. "iOS_17.4_Simulator_Runtime.dmg"
+	Add {
+		#[arg(long)]
+		image: Utf8PathBuf,
+	},
+	/// Deletes a runtime image, or every image not used recently
+	Delete {
+		/// An image UUID, or a runtime identifier like "com.apple.CoreSimulator.SimRuntime.iOS-17-4"
+		#[arg(
+			long,
+			required_unless_present = "not_used_since_days",
+			conflicts_with = "not_used_since_days"
+		)]
+		identifier: Option<String>,
+
+		/// Deletes every image not used in this many days
+		#[arg(long)]
+		not_used_since_days: Option<u32>,
+
+		/// With --not-used-since-days, only prints what would be deleted
+		#[arg(long, requires = "not_used_since_days")]
+		dry_run: bool,
+	},
+	/// Lists which runtime each installed SDK will use
+	Match,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Keychain {
+	/// Adds a certificate as a trusted root, e.g. the CA of a local HTTPS server
+	AddRootCert {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// A PEM or DER encoded certificate
+		#[arg(long)]
+		cert: Utf8PathBuf,
+	},
+	/// Adds a certificate without trusting it as a root
+	AddCert {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// A PEM or DER encoded certificate
+		#[arg(long)]
+		cert: Utf8PathBuf,
+	},
+	/// Resets the keychain, removing any added certificates
+	Reset {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+	},
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Ui {
+	/// Light or dark mode
+	Appearance {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// Prints the current value if not provided
+		#[arg(long, value_enum)]
+		set: Option<simctl::ui::UiAppearance>,
+	},
+	/// The preferred Dynamic Type size
+	ContentSize {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// Prints the current value if not provided
+		#[arg(long, value_enum)]
+		set: Option<simctl::ui::ContentSize>,
+	},
+	/// The Increase Contrast accessibility setting
+	IncreaseContrast {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// Prints the current value if not provided
+		#[arg(long, value_enum)]
+		set: Option<simctl::ui::IncreaseContrast>,
+	},
+}
+
+#[derive(Subcommand, Debug)]
+pub enum StatusBar {
+	/// Overrides parts of the status bar
+	Override {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+
+		/// Starts from the classic "9:41, full bars, full battery" look,
+		/// which other flags can then override
+		#[arg(long)]
+		marketing: bool,
+
+		#[clap(flatten)]
+		overrides: simctl::status_bar::StatusBarOverrides,
+	},
+	/// Clears all status bar overrides
+	Clear {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+	},
+	/// Lists the current status bar overrides
+	List {
+		#[clap(flatten)]
+		booted_simulator: DeviceSimulatorBootedArgs,
+	},
 }
 
 #[derive(Args, Debug)]
@@ -191,3 +739,79 @@ pub struct Open {
 	#[arg(long, value_enum)]
 	pub well_known: WellKnown,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn cli_args_are_valid() {
+		use clap::CommandFactory;
+		CliArgs::command().debug_assert();
+	}
+
+	#[test]
+	fn device_set_is_global() {
+		for args in [
+			[
+				"apple-clis",
+				"xcrun",
+				"simctl",
+				"--device-set",
+				"/tmp/set",
+				"list",
+			],
+			[
+				"apple-clis",
+				"xcrun",
+				"simctl",
+				"list",
+				"--device-set",
+				"/tmp/set",
+			],
+		] {
+			match CliArgs::try_parse_from(args).unwrap().command {
+				Commands::XcRun(XcRun::Simctl(simctl)) => {
+					assert_eq!(
+						simctl.device_set.as_deref(),
+						Some(Utf8Path::new("/tmp/set"))
+					)
+				}
+				command => panic!("Unexpected command {:?}", command),
+			}
+		}
+	}
+
+	#[test]
+	fn parses_seconds() {
+		assert_eq!(
+			parse_seconds("0.5"),
+			Ok(std::time::Duration::from_millis(500))
+		);
+		assert_eq!(parse_seconds("0"), Ok(std::time::Duration::ZERO));
+		for invalid in ["-1", "NaN", "inf", "soon"] {
+			assert!(parse_seconds(invalid).is_err(), "{}", invalid);
+		}
+	}
+
+	#[test]
+	fn open_url_takes_positional_url() {
+		let parse = |args: &[&str]| {
+			let args = CliArgs::try_parse_from(
+				["apple-clis", "xcrun", "simctl", "open-url"]
+					.iter()
+					.chain(args),
+			)
+			.unwrap();
+			match args.command {
+				Commands::XcRun(XcRun::Simctl(simctl)) => match simctl.command {
+					Simctl::OpenUrl { url, .. } => url,
+					command => panic!("Unexpected command {:?}", command),
+				},
+				command => panic!("Unexpected command {:?}", command),
+			}
+		};
+		assert_eq!(parse(&["myapp://link", "--booted"]), "myapp://link");
+		assert_eq!(parse(&["myapp://link", "iPhone 15"]), "myapp://link");
+	}
+}
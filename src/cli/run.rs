@@ -1,8 +1,11 @@
 use std::fs::File;
 use std::io::{BufRead, Write};
 
-use crate::cli::{self, CodeSign, Commands, Init, IosDeploy, Security, Simctl, Spctl, XcRun};
-use crate::ios_deploy::IosDeployCLIInstance;
+use crate::cli::{
+	self, CodeSign, Commands, Init, IosDeploy, IosDeployApps, IosDeployFiles, Keychain, Location,
+	Privacy, Runtime, Security, Simctl, Spctl, StatusBar, TopLevelCliArgs, Ui, XcRun,
+};
+use crate::ios_deploy::{upload::UploadProgress, IosDeployCLIInstance};
 use crate::open::OpenCLIInstance;
 use crate::prelude::*;
 use crate::shared::identifiers::DeviceName;
@@ -110,7 +113,7 @@ fn cmd_init(cmd: Init) -> CResult {
 }
 
 #[instrument(skip_all)]
-fn cmd_ios_deploy(cmd: IosDeploy) -> CResult {
+fn cmd_ios_deploy(args: &TopLevelCliArgs, cmd: IosDeploy) -> CResult {
 	let ios_deploy_instance = IosDeployCLIInstance::new()?;
 	match cmd {
 		IosDeploy::Detect { config } => {
@@ -126,19 +129,170 @@ fn cmd_ios_deploy(cmd: IosDeploy) -> CResult {
 		IosDeploy::Upload {
 			app_path,
 			auto_detect_config,
+			device_filter,
+			config,
 		} => {
 			let path = app_path.resolve()?;
 			let devices = ios_deploy_instance.detect_devices(&auto_detect_config)?;
-			let device = match devices.first() {
-				Some(d) => d,
-				None => Err(eyre!("No devices found to upload to"))?,
-			};
-			ios_deploy_instance.upload_bundle(device, path)?.success()?;
+			let device = device_filter.select(&devices)?;
+			info!(%device, "Uploading to device");
+			let output =
+				ios_deploy_instance.upload_bundle_with_progress(device, path, &config, |progress| {
+					match args.machine() {
+						true => match serde_json::to_string(progress) {
+							Ok(json) => println!("{}", json),
+							Err(err) => warn!(%err, "Failed to serialize upload progress"),
+						},
+						false => print_upload_progress(progress),
+					}
+				})?;
+			if !args.machine() {
+				eprintln!();
+			}
+			output.success()?;
+			to_json(output)
+		}
+		IosDeploy::Apps(cmd) => match cmd {
+			IosDeployApps::List { device } => {
+				let device = select_physical_device(&ios_deploy_instance, device)?;
+				let output = ios_deploy_instance.list_bundle_ids(&device)?;
+				let bundle_ids = output.success()?;
+				if !args.machine() {
+					for bundle_id in bundle_ids {
+						println!("{}", bundle_id);
+					}
+				}
+				to_json(bundle_ids)
+			}
+			IosDeployApps::Exists { device, bundle_id } => {
+				let device = select_physical_device(&ios_deploy_instance, device)?;
+				let output = ios_deploy_instance.app_exists(&device, &bundle_id)?;
+				let exists = *output.success()?;
+				info!(%bundle_id, exists, "Checked whether the app is installed on {}", device);
+				to_json(exists)
+			}
+			IosDeployApps::Uninstall { device, bundle_id } => {
+				let device = select_physical_device(&ios_deploy_instance, device)?;
+				ios_deploy_instance
+					.uninstall_app(&device, &bundle_id)?
+					.success()?;
+				Ok(None)
+			}
+		},
+		IosDeploy::Files(cmd) => match cmd {
+			IosDeployFiles::List {
+				device,
+				bundle_id,
+				path,
+			} => {
+				let device = select_physical_device(&ios_deploy_instance, device)?;
+				let output = ios_deploy_instance.list_app_files(&device, &bundle_id, path.as_deref())?;
+				let paths = output.success()?;
+				if !args.machine() {
+					for path in paths {
+						println!("{}", path);
+					}
+				}
+				to_json(paths)
+			}
+			IosDeployFiles::Download {
+				device,
+				bundle_id,
+				path,
+				output,
+			} => {
+				let device = select_physical_device(&ios_deploy_instance, device)?;
+				ios_deploy_instance
+					.download_app_files(&device, &bundle_id, path.as_deref(), &output)?
+					.success()?;
+				info!(%output, "Downloaded app files");
+				Ok(None)
+			}
+			IosDeployFiles::Upload {
+				device,
+				bundle_id,
+				file,
+				path,
+			} => {
+				let device = select_physical_device(&ios_deploy_instance, device)?;
+				ios_deploy_instance
+					.upload_app_file(&device, &bundle_id, &file, &path)?
+					.success()?;
+				Ok(None)
+			}
+			IosDeployFiles::Mkdir {
+				device,
+				bundle_id,
+				path,
+			} => {
+				let device = select_physical_device(&ios_deploy_instance, device)?;
+				ios_deploy_instance
+					.mkdir_app_container(&device, &bundle_id, &path)?
+					.success()?;
+				Ok(None)
+			}
+			IosDeployFiles::Rm {
+				device,
+				bundle_id,
+				path,
+			} => {
+				let device = select_physical_device(&ios_deploy_instance, device)?;
+				ios_deploy_instance
+					.rm_app_container(&device, &bundle_id, &path)?
+					.success()?;
+				Ok(None)
+			}
+		},
+		IosDeploy::Watch { config } => {
+			info!(
+				timeout = config.timeout,
+				"Watching for devices connecting or disconnecting, press Ctrl+C to stop"
+			);
+			for event in ios_deploy_instance.watch_devices(config) {
+				match event {
+					Ok(event) => match args.machine() {
+						true => println!("{}", serde_json::to_string(&event)?),
+						false => info!(device = %event.device(), ?event, "Device changed"),
+					},
+					// e.g. ios-deploy crashing, which shouldn't stop a long running watch
+					Err(err) => {
+						warn!(%err, "Failed to detect devices, retrying");
+						std::thread::sleep(std::time::Duration::from_secs(1));
+					}
+				}
+			}
 			Ok(None)
 		}
 	}
 }
 
+fn select_physical_device(
+	ios_deploy_instance: &IosDeployCLIInstance,
+	args: cli::PhysicalDeviceArgs,
+) -> color_eyre::Result<ios_deploy::detect::Device> {
+	let devices = ios_deploy_instance.detect_devices(&args.detect_config)?;
+	Ok(args.filter.select(&devices)?.clone())
+}
+
+/// Redraws a single line progress bar on stderr
+fn print_upload_progress(progress: &UploadProgress) {
+	const WIDTH: usize = 30;
+	let percent = progress.overall_percent().unwrap_or(0.0).clamp(0.0, 100.0);
+	let filled = (percent / 100.0 * WIDTH as f64).round() as usize;
+	let stage = match progress {
+		UploadProgress::Copying(_) => "Copying",
+		UploadProgress::Installing(_) => "Installing",
+	};
+	eprint!(
+		"\r\x1b[2K{:<10} [{}{}] {:>3.0}% {}",
+		stage,
+		"#".repeat(filled),
+		" ".repeat(WIDTH - filled),
+		percent,
+		progress.status().unwrap_or_default()
+	);
+}
+
 #[instrument(skip_all)]
 fn cmd_security(cmd: Security) -> CResult {
 	let security_instance = security::SecurityCLIInstance::new()?;
@@ -202,12 +356,19 @@ fn cmd_code_sign(cmd: CodeSign) -> CResult {
 }
 
 #[instrument(skip_all)]
-fn cmd_xcrun(cmd: XcRun) -> CResult {
+fn cmd_xcrun(args: &TopLevelCliArgs, cmd: XcRun) -> CResult {
 	let xcrun_instance = XcRunInstance::new()?;
 	match cmd {
-		XcRun::Simctl(simctl) => {
-			let simctl_instance = xcrun_instance.simctl();
-			match simctl {
+		XcRun::Simctl(simctl_args) => {
+			let cli::SimctlArgs {
+				device_set,
+				command,
+			} = *simctl_args;
+			let simctl_instance = match device_set {
+				Some(device_set) => xcrun_instance.simctl().with_device_set(device_set),
+				None => xcrun_instance.simctl(),
+			};
+			match command {
 				Simctl::List => {
 					let devices = simctl_instance.list()?;
 					let devices = devices.success()?.devices().collect::<Vec<_>>();
@@ -245,6 +406,453 @@ fn cmd_xcrun(cmd: XcRun) -> CResult {
 						}
 					}
 				}
+				Simctl::OpenUrl {
+					booted_simulator,
+					url,
+				} => {
+					let device = booted_simulator.resolve(&simctl_instance)?;
+					info!(%device, %url, "Opening URL in simulator");
+					simctl_instance.open_url(&device, url)?.success()?;
+					Ok(None)
+				}
+				Simctl::Push {
+					booted_simulator,
+					bundle_id,
+					args,
+				} => {
+					let device = booted_simulator.resolve(&simctl_instance)?;
+					let bundle_id = bundle_id.resolve()?;
+					let output = match args.resolve()? {
+						simctl::push::CliPushPayload::File(path) => {
+							simctl_instance.push_file(&device, &bundle_id, path)?
+						}
+						simctl::push::CliPushPayload::Payload(payload) => {
+							simctl_instance.push(&device, &bundle_id, &payload)?
+						}
+					};
+					output.success()?;
+					to_json(output)
+				}
+				Simctl::Privacy(privacy) => {
+					let output = match privacy {
+						Privacy::Grant {
+							booted_simulator,
+							service,
+							bundle_id,
+						} => {
+							let device = booted_simulator.resolve(&simctl_instance)?;
+							let bundle_id = bundle_id.resolve()?;
+							simctl_instance.privacy_grant(&device, service, &bundle_id)?
+						}
+						Privacy::Revoke {
+							booted_simulator,
+							service,
+							bundle_id,
+						} => {
+							let device = booted_simulator.resolve(&simctl_instance)?;
+							let bundle_id = bundle_id.resolve()?;
+							simctl_instance.privacy_revoke(&device, service, &bundle_id)?
+						}
+						Privacy::Reset {
+							booted_simulator,
+							service,
+							bundle_id,
+						} => {
+							let device = booted_simulator.resolve(&simctl_instance)?;
+							simctl_instance.privacy_reset(&device, service, bundle_id.as_deref())?
+						}
+					};
+					output.success()?;
+					Ok(None)
+				}
+				Simctl::Location(location) => {
+					let output = match location {
+						Location::Set {
+							booted_simulator,
+							coordinate,
+						} => {
+							let device = booted_simulator.resolve(&simctl_instance)?;
+							simctl_instance.location_set(&device, coordinate)?
+						}
+						Location::Clear { booted_simulator } => {
+							let device = booted_simulator.resolve(&simctl_instance)?;
+							simctl_instance.location_clear(&device)?
+						}
+						Location::Route {
+							booted_simulator,
+							waypoints,
+							speed,
+							distance,
+							interval,
+						} => {
+							let device = booted_simulator.resolve(&simctl_instance)?;
+							let mut route = match waypoints.gpx {
+								Some(gpx) => simctl::location::LocationRoute::from_gpx_file(gpx)?,
+								None => simctl::location::LocationRoute::new(waypoints.waypoints),
+							};
+							route.speed = speed;
+							route.pacing = match (distance, interval) {
+								(Some(distance), _) => Some(simctl::location::RoutePacing::Distance(distance)),
+								(None, Some(interval)) => Some(simctl::location::RoutePacing::Interval(interval)),
+								(None, None) => None,
+							};
+							info!(
+								waypoints = route.waypoints.len(),
+								"Starting simulated route"
+							);
+							simctl_instance.location_run_route(&device, &route)?
+						}
+					};
+					output.success()?;
+					Ok(None)
+				}
+				Simctl::StatusBar(status_bar) => match status_bar {
+					StatusBar::Override {
+						booted_simulator,
+						marketing,
+						overrides,
+					} => {
+						let device = booted_simulator.resolve(&simctl_instance)?;
+						let overrides = match marketing {
+							true => overrides.or(simctl::status_bar::StatusBarOverrides::marketing()),
+							false => overrides,
+						};
+						if overrides == simctl::status_bar::StatusBarOverrides::default() {
+							Err(eyre!(
+								"No status bar overrides provided, try --marketing or e.g. --time 9:41"
+							))?
+						}
+						simctl_instance
+							.status_bar_override(&device, &overrides)?
+							.success()?;
+						to_json(overrides)
+					}
+					StatusBar::Clear { booted_simulator } => {
+						let device = booted_simulator.resolve(&simctl_instance)?;
+						simctl_instance.status_bar_clear(&device)?.success()?;
+						Ok(None)
+					}
+					StatusBar::List { booted_simulator } => {
+						let device = booted_simulator.resolve(&simctl_instance)?;
+						let output = simctl_instance.status_bar_list(&device)?;
+						to_json(output.success()?)
+					}
+				},
+				Simctl::Ui(ui) => {
+					let output = match ui {
+						Ui::Appearance {
+							booted_simulator,
+							set,
+						} => {
+							let device = booted_simulator.resolve(&simctl_instance)?;
+							match set {
+								Some(appearance) => simctl_instance.set_ui_appearance(&device, appearance)?,
+								None => simctl_instance.ui_appearance(&device)?,
+							}
+						}
+						Ui::ContentSize {
+							booted_simulator,
+							set,
+						} => {
+							let device = booted_simulator.resolve(&simctl_instance)?;
+							match set {
+								Some(content_size) => simctl_instance.set_ui_content_size(&device, content_size)?,
+								None => simctl_instance.ui_content_size(&device)?,
+							}
+						}
+						Ui::IncreaseContrast {
+							booted_simulator,
+							set,
+						} => {
+							let device = booted_simulator.resolve(&simctl_instance)?;
+							match set {
+								Some(increase_contrast) => {
+									simctl_instance.set_ui_increase_contrast(&device, increase_contrast)?
+								}
+								None => simctl_instance.ui_increase_contrast(&device)?,
+							}
+						}
+					};
+					output.success()?;
+					to_json(output)
+				}
+				Simctl::Pbcopy {
+					booted_simulator,
+					text,
+				} => {
+					let device = booted_simulator.resolve(&simctl_instance)?;
+					let text = match text {
+						Some(text) => text,
+						None => std::io::read_to_string(std::io::stdin())?,
+					};
+					simctl_instance.pbcopy(&device, text)?.success()?;
+					Ok(None)
+				}
+				Simctl::Pbpaste { booted_simulator } => {
+					let device = booted_simulator.resolve(&simctl_instance)?;
+					let output = simctl_instance.pbpaste(&device)?;
+					info!(contents = %output.success()?, "Pasteboard contents");
+					to_json(output)
+				}
+				Simctl::AddMedia {
+					booted_simulator,
+					file,
+				} => {
+					let device = booted_simulator.resolve(&simctl_instance)?;
+					simctl_instance.add_media(&device, &file)?.success()?;
+					info!(files = file.len(), "Added media");
+					Ok(None)
+				}
+				Simctl::Keychain(keychain) => {
+					let output = match keychain {
+						Keychain::AddRootCert {
+							booted_simulator,
+							cert,
+						} => {
+							let device = booted_simulator.resolve(&simctl_instance)?;
+							simctl_instance.keychain_add_root_cert(&device, cert)?
+						}
+						Keychain::AddCert {
+							booted_simulator,
+							cert,
+						} => {
+							let device = booted_simulator.resolve(&simctl_instance)?;
+							simctl_instance.keychain_add_cert(&device, cert)?
+						}
+						Keychain::Reset { booted_simulator } => {
+							let device = booted_simulator.resolve(&simctl_instance)?;
+							simctl_instance.keychain_reset(&device)?
+						}
+					};
+					output.success()?;
+					Ok(None)
+				}
+				Simctl::Diagnostics {
+					output,
+					device,
+					bundle_id,
+					no_simctl_diagnose,
+				} => {
+					let options = simctl::diagnostics::DiagnosticsOptions {
+						device: device.map(|device| device.parse()).transpose()?,
+						bundle_id,
+						simctl_diagnose: !no_simctl_diagnose,
+					};
+					let report = simctl_instance.collect_diagnostics(&options, output)?;
+					for skipped in &report.skipped {
+						warn!(item = %skipped.item, reason = %skipped.reason, "Not included in diagnostics");
+					}
+					info!(archive = %report.archive, included = report.included.len(), "Collected diagnostics");
+					to_json(report)
+				}
+				Simctl::Runtime(runtime) => match runtime {
+					Runtime::List => {
+						let output = simctl_instance.runtime_list()?;
+						let list = output.success()?;
+						for image in list.images() {
+							info!(
+								runtime = %image.runtime_identifier,
+								build = %image.build,
+								state = %image.state,
+								size_gb = image.size_bytes as f64 / 1e9,
+								identifier = %image.identifier,
+								"Runtime image"
+							);
+						}
+						info!(
+							total_size_gb = list.total_size_bytes() as f64 / 1e9,
+							"Total disk usage"
+						);
+						to_json(output)
+					}
+					Runtime::Add { image } => {
+						simctl_instance.runtime_add(image)?.success()?;
+						Ok(None)
+					}
+					Runtime::Delete {
+						identifier,
+						not_used_since_days,
+						dry_run,
+					} => {
+						let output = match (identifier, not_used_since_days) {
+							(Some(identifier), _) => {
+								let list = simctl_instance.runtime_list()?;
+								let list = list.success()?;
+								// resolve runtime identifiers to the UUID of their image
+								let image = list
+									.images()
+									.into_iter()
+									.find(|image| {
+										image.identifier == identifier
+											|| image.runtime_identifier.as_str() == identifier
+									})
+									.wrap_err_with(|| {
+										format!("No installed runtime image matches {:?}", identifier)
+									})?;
+								simctl_instance.runtime_delete(&image.identifier)?
+							}
+							(None, Some(days)) => simctl_instance.runtime_delete_not_used_since(days, dry_run)?,
+							(None, None) => {
+								unreachable!("clap requires one of --identifier or --not-used-since-days")
+							}
+						};
+						output.success()?;
+						to_json(output)
+					}
+					Runtime::Match => {
+						let output = simctl_instance.runtime_match_list()?;
+						output.success()?;
+						to_json(output)
+					}
+				},
+				Simctl::Watch { interval } => {
+					info!(
+						?interval,
+						"Watching for simulator changes, press Ctrl+C to stop"
+					);
+					for event in simctl_instance.watch(interval) {
+						match event {
+							Ok(event) => match args.machine() {
+								true => println!("{}", serde_json::to_string(&event)?),
+								false => info!(?event, "Simulator changed"),
+							},
+							// e.g. `simctl list` timing out, which shouldn't stop a long running watch
+							Err(err) => warn!(%err, "Failed to list simulators, retrying"),
+						}
+					}
+					Ok(None)
+				}
+				Simctl::Ensure {
+					file,
+					delete_unlisted,
+					dry_run,
+				} => {
+					let matrix = simctl::ensure::SimulatorMatrix::from_toml_file(&file)?;
+					let report = simctl_instance.ensure(&matrix, delete_unlisted, dry_run)?;
+					for unavailable in &report.plan.unavailable {
+						warn!(device = %unavailable.spec.device, reason = %unavailable.reason, "Cannot ensure simulator");
+					}
+					info!(
+						existing = report.plan.existing.len(),
+						create = report.plan.create.len(),
+						unavailable = report.plan.unavailable.len(),
+						unlisted = report.plan.unlisted.len(),
+						created = report.created.len(),
+						deleted = report.deleted.len(),
+						"Ensured simulators"
+					);
+					to_json(report)
+				}
+				Simctl::Spawn {
+					booted_simulator,
+					env,
+					command,
+				} => {
+					let device = booted_simulator.resolve(&simctl_instance)?;
+					let mut command = command.into_iter();
+					let executable = command.next().wrap_err("No executable provided to spawn")?;
+					let mut spawn = simctl::spawn::SpawnCommand::new(executable).with_args(command);
+					for (key, value) in env {
+						spawn = spawn.with_env_var(key, value);
+					}
+					let output = simctl_instance.spawn(&device, &spawn)?;
+					if args.human() {
+						if let Some(stdout) = output.stdout() {
+							print!("{}", stdout);
+						}
+						if let Some(stderr) = output.stderr() {
+							eprint!("{}", stderr);
+						}
+					}
+					output.success()?;
+					to_json(output)
+				}
+				Simctl::Getenv {
+					booted_simulator,
+					var_name,
+				} => {
+					let device = booted_simulator.resolve(&simctl_instance)?;
+					let output = simctl_instance.getenv(&device, &var_name)?;
+					info!(%var_name, value = %output.success()?, "Read environment variable");
+					to_json(output)
+				}
+				Simctl::Logs {
+					booted_simulator,
+					follow,
+					subsystem,
+					category,
+					process,
+					level,
+					last,
+				} => {
+					let device = booted_simulator.resolve(&simctl_instance)?;
+					let mut predicate = simctl::log::LogPredicate::new();
+					if let Some(subsystem) = subsystem {
+						predicate = predicate.with_subsystem(subsystem);
+					}
+					if let Some(category) = category {
+						predicate = predicate.with_category(category);
+					}
+					if let Some(process) = process {
+						predicate = predicate.with_process(process);
+					}
+					match follow {
+						true => {
+							info!(%predicate, "Streaming logs, press Ctrl+C to stop");
+							for event in simctl_instance.log_stream(&device, &predicate, level)? {
+								let event = event?;
+								match args.machine() {
+									true => println!("{}", serde_json::to_string(&event)?),
+									false => println!("{}", event),
+								}
+							}
+							Ok(None)
+						}
+						false => {
+							let range = simctl::log::LogShowRange::Last(last);
+							let output = simctl_instance.log_show(&device, &predicate, level, &range)?;
+							let events = output.success()?;
+							if args.human() {
+								for event in events {
+									println!("{}", event);
+								}
+							}
+							to_json(output)
+						}
+					}
+				}
+				Simctl::Screenshot {
+					booted_simulator,
+					output,
+					options,
+				} => {
+					let device = booted_simulator.resolve(&simctl_instance)?;
+					let output = simctl_instance.screenshot(&device, output, &options)?;
+					info!(path = %output.success()?, "Saved screenshot");
+					to_json(output)
+				}
+				Simctl::Record {
+					booted_simulator,
+					output,
+					duration,
+					options,
+				} => {
+					let device = booted_simulator.resolve(&simctl_instance)?;
+					let recording = simctl_instance.record_video(&device, output, &options)?;
+					match duration {
+						Some(duration) => {
+							info!(?duration, "Recording video");
+							std::thread::sleep(duration);
+						}
+						None => {
+							info!("Recording video, press Enter to stop");
+							std::io::stdin().lock().read_line(&mut String::new())?;
+						}
+					}
+					let output = recording.stop()?;
+					info!(path = %output.success()?, "Saved video");
+					to_json(output)
+				}
 			}
 		}
 	}
@@ -261,15 +869,16 @@ fn cmd_open(cmd: cli::Open) -> CResult {
 
 #[instrument(skip_all, level = "info", ret)]
 pub fn run(
+	args: &TopLevelCliArgs,
 	command: Commands,
 ) -> std::result::Result<Option<serde_json::Value>, color_eyre::Report> {
 	match command {
 		Commands::Init(cmd) => cmd_init(cmd),
-		Commands::IosDeploy(cmd) => cmd_ios_deploy(cmd),
+		Commands::IosDeploy(cmd) => cmd_ios_deploy(args, cmd),
 		Commands::Security(cmd) => cmd_security(cmd),
 		Commands::Spctl(cmd) => cmd_spctl(cmd),
 		Commands::CodeSign(cmd) => cmd_code_sign(cmd),
-		Commands::XcRun(cmd) => cmd_xcrun(cmd),
+		Commands::XcRun(cmd) => cmd_xcrun(args, cmd),
 		Commands::Open(cmd) => cmd_open(cmd),
 	}
 }
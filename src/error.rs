@@ -59,6 +59,20 @@ pub enum Error {
 		err: Option<std::io::Error>,
 	},
 
+	#[error("Error reading or writing {path}: {err}")]
+	FileIo {
+		path: Utf8PathBuf,
+		#[source]
+		err: std::io::Error,
+	},
+
+	/// Spawning, signalling or waiting on a child process outside of [bossy]
+	#[error("Error managing child process: {0}")]
+	ChildProcessIo(#[source] std::io::Error),
+
+	#[error("Error parsing TOML: {0}")]
+	ParseToml(#[from] toml::de::Error),
+
 	#[error("Error converting path to UTF-8: {0}")]
 	PathNotUtf8(#[from] camino::FromPathBufError),
 
@@ -83,6 +97,34 @@ pub enum Error {
 		err: Option<std::io::Error>,
 	},
 
+	/// See [crate::xcrun::simctl::push::PushPayload::MAX_SIZE]
+	#[error("Push notification payload is {size} bytes, larger than the maximum of {max} bytes")]
+	PushPayloadTooLarge { size: usize, max: usize },
+
+	/// See [crate::xcrun::simctl::location::parse_gpx_waypoints]
+	#[error("No <trkpt>, <rtept> or <wpt> waypoints found in GPX file")]
+	GpxWithoutWaypoints,
+
+	/// See [crate::xcrun::simctl::XcRunSimctlInstance::location_run_route]
+	#[error("A location route needs at least two waypoints, but only {waypoints} were given")]
+	LocationRouteTooShort { waypoints: usize },
+
+	/// See [crate::xcrun::simctl::status_bar::StatusBarOverrides]
+	#[error("Status bar override {name} was {value}, but must be at most {max}")]
+	StatusBarOverrideOutOfRange {
+		name: &'static str,
+		value: u8,
+		max: u8,
+	},
+
+	/// See [crate::ios_deploy::detect::DeviceFilter::select]
+	#[error("No detected device matches {filter}, detected devices: [{candidates}]")]
+	NoMatchingDevice { filter: String, candidates: String },
+
+	/// See [crate::ios_deploy::detect::DeviceFilter::select]
+	#[error("Multiple detected devices match {filter}, pass a name, identifier, model or interface to choose one of: [{candidates}]")]
+	AmbiguousDevice { filter: String, candidates: String },
+
 	#[error("Couldn't locate the stderr output stream even though the command errored: {err}")]
 	CannotLocateStderrStream { err: bossy::Error },
 }
@@ -1,7 +1,10 @@
 use crate::prelude::*;
 
+pub mod apps;
 pub mod detect;
+pub mod events;
 pub mod upload;
+pub mod watch;
 
 #[derive(Debug)]
 pub struct IosDeployCLIInstance {
@@ -9,3 +12,12 @@ pub struct IosDeployCLIInstance {
 }
 
 impl_exec_instance!(IosDeployCLIInstance, "ios-deploy");
+
+impl IosDeployCLIInstance {
+	/// For commands whose output is streamed, which [bossy] can't do
+	fn std_command(&self) -> std::process::Command {
+		let mut command = std::process::Command::new(&self.exec_path);
+		command.env_clear();
+		command
+	}
+}
@@ -0,0 +1,233 @@
+//! Inspecting and managing apps already installed on a real device,
+//! including the files in their data containers.
+
+use crate::prelude::*;
+
+use super::{detect::Device, IosDeployCLIInstance};
+
+pub use output::*;
+mod output;
+
+impl IosDeployCLIInstance {
+	fn device_command(&self, device: &Device) -> bossy::Command {
+		self
+			.bossy_command()
+			.with_args(["--id", &device.device_identifier])
+	}
+
+	fn app_command(&self, device: &Device, bundle_id: &str) -> bossy::Command {
+		self
+			.device_command(device)
+			.with_args(["--bundle_id", bundle_id])
+	}
+
+	/// Lists the bundle identifiers of every app installed on the device
+	#[instrument(ret, skip_all, fields(device = %device.device_identifier))]
+	pub fn list_bundle_ids(&self, device: &Device) -> Result<ListBundleIdsOutput> {
+		ListBundleIdsOutput::from_bossy_result(
+			self
+				.device_command(device)
+				.with_arg("--list_bundle_id")
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Checks whether an app is installed on the device
+	#[instrument(ret, skip_all, fields(device = %device.device_identifier, %bundle_id))]
+	pub fn app_exists(&self, device: &Device, bundle_id: &str) -> Result<AppExistsOutput> {
+		AppExistsOutput::from_bossy_result(
+			self
+				.app_command(device, bundle_id)
+				.with_arg("--exists")
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Uninstalls an app, without installing anything
+	#[instrument(ret, skip_all, fields(device = %device.device_identifier, %bundle_id))]
+	pub fn uninstall_app(&self, device: &Device, bundle_id: &str) -> Result<AppsOutput> {
+		AppsOutput::from_bossy_result(
+			self
+				.app_command(device, bundle_id)
+				.with_arg("--uninstall_only")
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Lists the files in an app's data container,
+	/// or only those under `container_path`, e.g. "/Documents"
+	#[instrument(ret, skip_all, fields(device = %device.device_identifier, %bundle_id, ?container_path))]
+	pub fn list_app_files(
+		&self,
+		device: &Device,
+		bundle_id: &str,
+		container_path: Option<&str>,
+	) -> Result<ListAppFilesOutput> {
+		let list = match container_path {
+			Some(path) => format!("--list={}", path),
+			None => "--list".to_owned(),
+		};
+		ListAppFilesOutput::from_bossy_result(
+			self
+				.app_command(device, bundle_id)
+				.with_arg(list)
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Downloads an app's data container, or only `container_path` within it,
+	/// into the local `destination` directory.
+	/// e.g. `Some("/Library/Application Support/db.sqlite")`
+	#[instrument(ret, skip_all, fields(device = %device.device_identifier, %bundle_id, ?container_path, destination = %destination.as_ref()))]
+	pub fn download_app_files(
+		&self,
+		device: &Device,
+		bundle_id: &str,
+		container_path: Option<&str>,
+		destination: impl AsRef<Utf8Path>,
+	) -> Result<AppsOutput> {
+		let download = match container_path {
+			Some(path) => format!("--download={}", path),
+			None => "--download".to_owned(),
+		};
+		AppsOutput::from_bossy_result(
+			self
+				.app_command(device, bundle_id)
+				.with_arg(download)
+				.with_args(["--to", destination.as_ref().as_str()])
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Uploads a local file to `container_path` in an app's data container
+	#[instrument(ret, skip_all, fields(device = %device.device_identifier, %bundle_id, file = %file.as_ref(), %container_path))]
+	pub fn upload_app_file(
+		&self,
+		device: &Device,
+		bundle_id: &str,
+		file: impl AsRef<Utf8Path>,
+		container_path: &str,
+	) -> Result<AppsOutput> {
+		AppsOutput::from_bossy_result(
+			self
+				.app_command(device, bundle_id)
+				.with_args(["--upload", file.as_ref().as_str()])
+				.with_args(["--to", container_path])
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Creates a directory in an app's data container
+	#[instrument(ret, skip_all, fields(device = %device.device_identifier, %bundle_id, %container_path))]
+	pub fn mkdir_app_container(
+		&self,
+		device: &Device,
+		bundle_id: &str,
+		container_path: &str,
+	) -> Result<AppsOutput> {
+		AppsOutput::from_bossy_result(
+			self
+				.app_command(device, bundle_id)
+				.with_args(["--mkdir", container_path])
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Removes a file or empty directory from an app's data container
+	#[instrument(ret, skip_all, fields(device = %device.device_identifier, %bundle_id, %container_path))]
+	pub fn rm_app_container(
+		&self,
+		device: &Device,
+		bundle_id: &str,
+		container_path: &str,
+	) -> Result<AppsOutput> {
+		AppsOutput::from_bossy_result(
+			self
+				.app_command(device, bundle_id)
+				.with_args(["--rm", container_path])
+				.run_and_wait_for_output(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ios_deploy::detect::DeviceInterface;
+
+	#[test]
+	fn apps_args() {
+		// Safety: `/bin/echo` is a valid executable
+		let ios_deploy = unsafe { IosDeployCLIInstance::new_unchecked("/bin/echo") };
+		let device = Device {
+			device_identifier: "00008030-001A".into(),
+			device_name: "Lab iPhone".into(),
+			model_name: "iPhone 12".parse().unwrap(),
+			interface: DeviceInterface::Usb,
+		};
+		let app = "--id 00008030-001A --bundle_id com.example.app";
+		let stdout = |output: AppsOutput| match output {
+			AppsOutput::SuccessUnImplemented { stdout } => stdout,
+			output => panic!("Unexpected output {:?}", output),
+		};
+		let listed = |container_path| {
+			ios_deploy
+				.list_app_files(&device, "com.example.app", container_path)
+				.unwrap()
+				.success()
+				.unwrap()
+				.clone()
+		};
+
+		assert_eq!(listed(None), [format!("{app} --list")]);
+		assert_eq!(
+			listed(Some("/Documents")),
+			[format!("{app} --list=/Documents")]
+		);
+		assert_eq!(
+			stdout(
+				ios_deploy
+					.download_app_files(&device, "com.example.app", Some("/Documents"), "out")
+					.unwrap()
+			),
+			format!("{app} --download=/Documents --to out\n")
+		);
+		assert_eq!(
+			stdout(
+				ios_deploy
+					.download_app_files(&device, "com.example.app", None, "out")
+					.unwrap()
+			),
+			format!("{app} --download --to out\n")
+		);
+		assert_eq!(
+			stdout(
+				ios_deploy
+					.upload_app_file(
+						&device,
+						"com.example.app",
+						"db.sqlite",
+						"/Documents/db.sqlite"
+					)
+					.unwrap()
+			),
+			format!("{app} --upload db.sqlite --to /Documents/db.sqlite\n")
+		);
+		assert_eq!(
+			stdout(
+				ios_deploy
+					.mkdir_app_container(&device, "com.example.app", "/Documents/cache")
+					.unwrap()
+			),
+			format!("{app} --mkdir /Documents/cache\n")
+		);
+		assert_eq!(
+			stdout(
+				ios_deploy
+					.rm_app_container(&device, "com.example.app", "/Documents/cache")
+					.unwrap()
+			),
+			format!("{app} --rm /Documents/cache\n")
+		);
+	}
+}
@@ -0,0 +1,276 @@
+use crate::prelude::*;
+
+/// Lines of `ios-deploy` output, without its `[....] Waiting for iOS device`
+/// style status lines
+fn output_lines(stdout: &str) -> impl Iterator<Item = &str> {
+	stdout
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('['))
+}
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum ListBundleIdsOutput {
+	BundleIds {
+		bundle_ids: Vec<String>,
+	},
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented {
+		stdout: String,
+	},
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented {
+		stderr: String,
+	},
+}
+
+impl CommandNomParsable for ListBundleIdsOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn success_from_str(input: &str) -> Self {
+		Self::BundleIds {
+			bundle_ids: output_lines(input).map(String::from).collect(),
+		}
+	}
+}
+
+impl PublicCommandOutput for ListBundleIdsOutput {
+	type PrimarySuccess = Vec<String>;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			Self::BundleIds { bundle_ids } => Ok(bundle_ids),
+			_ => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum AppExistsOutput {
+	Installed,
+
+	NotInstalled,
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented {
+		stdout: String,
+	},
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented {
+		stderr: String,
+	},
+}
+
+impl CommandNomParsable for AppExistsOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn success_from_str(input: &str) -> Self {
+		match output_lines(input).last() {
+			Some("true") => Self::Installed,
+			Some("false") => Self::NotInstalled,
+			_ => Self::success_unimplemented(input.to_owned()),
+		}
+	}
+
+	/// `ios-deploy --exists` prints `false` then exits with an error
+	/// when the app isn't installed
+	fn from_bossy_result(result: bossy::Result<bossy::Output>) -> Result<Self> {
+		match result {
+			Ok(output) => Ok(Self::success_from_str(output.stdout_str()?)),
+			Err(err) => match err.output() {
+				Some(output) => match output_lines(output.stdout_str()?).last() {
+					Some("false") => Ok(Self::NotInstalled),
+					_ => Ok(Self::errored_from_str(output.stderr_str()?)),
+				},
+				None => Err(Error::CannotLocateStderrStream { err }),
+			},
+		}
+	}
+}
+
+impl PublicCommandOutput for AppExistsOutput {
+	type PrimarySuccess = bool;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			Self::Installed => Ok(&true),
+			Self::NotInstalled => Ok(&false),
+			_ => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum ListAppFilesOutput {
+	/// Paths relative to the app's data container, directories ending in `/`
+	Files { paths: Vec<String> },
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for ListAppFilesOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn success_from_str(input: &str) -> Self {
+		Self::Files {
+			paths: output_lines(input).map(String::from).collect(),
+		}
+	}
+}
+
+impl PublicCommandOutput for ListAppFilesOutput {
+	type PrimarySuccess = Vec<String>;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			Self::Files { paths } => Ok(paths),
+			_ => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+/// Output of uninstalling an app, or modifying its data container
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum AppsOutput {
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	/// No app with the bundle identifier is installed, e.g.
+	/// `Unable to find bundle with id: com.example.app`
+	ErrorAppNotFound { stderr: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for AppsOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn errored_from_str(input: &str) -> Self {
+		if input.contains("Unable to find bundle with id") {
+			let output = Self::ErrorAppNotFound {
+				stderr: input.to_owned(),
+			};
+			warn!(?output, "Parsed ios-deploy apps error");
+			return output;
+		}
+		Self::error_unimplemented(input.to_owned())
+	}
+}
+
+impl PublicCommandOutput for AppsOutput {
+	type PrimarySuccess = ();
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			Self::SuccessUnImplemented { .. } => Ok(&()),
+			Self::ErrorAppNotFound { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Check the app is installed with `ios-deploy apps exists`",
+			)),
+			Self::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_app_outputs() {
+		let status = "[....] Waiting for iOS device to be connected\n[....] Using 00008030-001A (D53pAP, iPhone 12, iphoneos, arm64e, 17.4, 21E219) a.k.a. 'Lab iPhone'.\n";
+
+		let output = ListBundleIdsOutput::success_from_str(&format!(
+			"{status}com.apple.mobilesafari\ncom.example.app\n"
+		));
+		assert_eq!(
+			output.success().unwrap(),
+			&vec!["com.apple.mobilesafari", "com.example.app"]
+		);
+
+		let output = AppExistsOutput::success_from_str(&format!("{status}true\n"));
+		assert_eq!(output.success().unwrap(), &true);
+		let output = AppExistsOutput::success_from_str(&format!("{status}false\n"));
+		assert_eq!(output.success().unwrap(), &false);
+
+		let output =
+			ListAppFilesOutput::success_from_str(&format!("{status}/Documents/\n/Documents/db.sqlite\n"));
+		assert_eq!(
+			output.success().unwrap(),
+			&vec!["/Documents/", "/Documents/db.sqlite"]
+		);
+	}
+
+	#[test]
+	fn app_exists_exit_status() {
+		let run = |script: &str| {
+			AppExistsOutput::from_bossy_result(
+				bossy::Command::pure("/bin/sh")
+					.with_args(["-c", script])
+					.run_and_wait_for_output(),
+			)
+			.unwrap()
+		};
+		// `ios-deploy --exists` exits with an error after printing "false"
+		let output = run("echo '[....] Waiting for iOS device'; echo false; exit 1");
+		assert_eq!(output.success().unwrap(), &false);
+		let output = run("echo '[ !! ] Timed out waiting for device' >&2; exit 1");
+		assert!(
+			matches!(output, AppExistsOutput::ErrorUnImplemented { ref stderr } if stderr.contains("Timed out"))
+		);
+	}
+
+	#[test]
+	fn apps_output_hints() {
+		let hint = |stderr: &str| match AppsOutput::errored_from_str(stderr).success() {
+			Err(Error::OutputErrored { help_hint, .. }) => help_hint,
+			result => panic!("Expected an error, got {:?}", result),
+		};
+		assert!(
+			hint("[ !! ] Unable to find bundle with id: com.example.app\n")
+				.unwrap()
+				.contains("apps exists")
+		);
+		assert_eq!(hint("[ !! ] Timed out waiting for device\n"), None);
+	}
+}
@@ -1,6 +1,12 @@
-use super::IosDeployCLIInstance;
+use super::{
+	events::{self, Event},
+	IosDeployCLIInstance,
+};
 use crate::prelude::*;
 
+pub use filter::*;
+mod filter;
+
 impl IosDeployCLIInstance {
 	#[instrument(ret, skip_all)]
 	pub fn detect_devices(&self, config: &DetectDevicesConfig) -> Result<Vec<Device>> {
@@ -27,48 +33,14 @@ impl IosDeployCLIInstance {
 
 		trace!(previous_output = %output, "Before processing ios-deploy JSON output");
 
-		// after every } close brace, adds a comma
-		// this is to handle { .. } \n { ... } even style messages
-		let output = format!("[{}]", output);
-		let output = output.replace("}{", "},{");
-
-		trace!(after_output = %output, "After processing ios-deploy JSON output");
-
-		#[derive(Debug, Deserialize)]
-		struct Event {
-			#[serde(rename(deserialize = "Interface"))]
-			interface: DeviceInterface,
-
-			#[serde(rename(deserialize = "Device"))]
-			device: DeviceDetected,
-		}
-
-		#[derive(Debug, Deserialize)]
-		struct DeviceDetected {
-			#[serde(rename(deserialize = "DeviceIdentifier"))]
-			device_identifier: String,
-
-			#[serde(rename(deserialize = "DeviceName"))]
-			device_name: String,
-
-			#[serde(rename(deserialize = "modelName"))]
-			model_name: ModelName,
-		}
-
-		let events = match serde_json::from_str::<Vec<Event>>(&output) {
-			Ok(events) => events,
-			Err(err) => {
-				error!(%output, "Failed to parse JSON output from ios-deploy");
-				Err(err)?
-			}
-		};
-		let devices = events
+		let devices = events::parse_events(&output)
 			.into_iter()
-			.map(|event| Device {
-				device_name: event.device.device_name,
-				device_identifier: event.device.device_identifier,
-				model_name: event.device.model_name,
-				interface: event.interface,
+			.filter_map(|event| match event {
+				Event::DeviceDetected(detected) => Some(Device::from(detected)),
+				event => {
+					debug!(?event, "Ignoring non device ios-deploy event");
+					None
+				}
 			})
 			.collect();
 
@@ -76,7 +48,7 @@ impl IosDeployCLIInstance {
 	}
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "cli", derive(clap::Args))]
 pub struct DetectDevicesConfig {
 	#[cfg_attr(feature = "cli", clap(long, default_value_t = 1))]
@@ -96,7 +68,7 @@ impl Default for DetectDevicesConfig {
 	}
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Device {
 	pub device_identifier: String,
 	pub device_name: String,
@@ -104,9 +76,29 @@ pub struct Device {
 	pub interface: DeviceInterface,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl std::fmt::Display for Device {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{} ({}, {} over {})",
+			self.device_name, self.model_name, self.device_identifier, self.interface
+		)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum DeviceInterface {
 	Usb,
 	Wifi,
 }
+
+impl std::fmt::Display for DeviceInterface {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			DeviceInterface::Usb => write!(f, "USB"),
+			DeviceInterface::Wifi => write!(f, "Wi-Fi"),
+		}
+	}
+}
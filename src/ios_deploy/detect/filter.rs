@@ -0,0 +1,194 @@
+use crate::prelude::*;
+
+use super::{Device, DeviceInterface};
+
+/// Selects a single [Device] from those detected by `ios-deploy`.
+/// Every criteria that is set must match, see [DeviceFilter::select].
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "cli", derive(clap::Args))]
+pub struct DeviceFilter {
+	/// The device's name, e.g. "Lab iPhone", matched case insensitively
+	#[cfg_attr(feature = "cli", arg(long = "device-name"))]
+	pub name: Option<String>,
+
+	/// The device's UDID
+	#[cfg_attr(feature = "cli", arg(long = "device-id"))]
+	pub identifier: Option<String>,
+
+	/// The device's model, e.g. "iPhone 12"
+	#[cfg_attr(feature = "cli", arg(long = "device-model"))]
+	pub model: Option<ModelName>,
+
+	/// Only consider devices connected over this interface.
+	/// Without it, USB is preferred over Wi-Fi
+	#[cfg_attr(feature = "cli", arg(long = "device-interface", value_enum))]
+	pub interface: Option<DeviceInterface>,
+}
+
+impl DeviceFilter {
+	pub fn with_name(mut self, name: impl Into<String>) -> Self {
+		self.name = Some(name.into());
+		self
+	}
+
+	pub fn with_identifier(mut self, identifier: impl Into<String>) -> Self {
+		self.identifier = Some(identifier.into());
+		self
+	}
+
+	pub fn with_model(mut self, model: ModelName) -> Self {
+		self.model = Some(model);
+		self
+	}
+
+	pub fn with_interface(mut self, interface: DeviceInterface) -> Self {
+		self.interface = Some(interface);
+		self
+	}
+
+	pub fn matches(&self, device: &Device) -> bool {
+		self
+			.name
+			.as_ref()
+			.map_or(true, |name| device.device_name.eq_ignore_ascii_case(name))
+			&& self.identifier.as_ref().map_or(true, |identifier| {
+				device.device_identifier.eq_ignore_ascii_case(identifier)
+			}) && self
+			.model
+			.as_ref()
+			.map_or(true, |model| &device.model_name == model)
+			&& self
+				.interface
+				.map_or(true, |interface| device.interface == interface)
+	}
+
+	/// Every matching device, with a device detected over both USB and Wi-Fi
+	/// only listed once, preferring USB.
+	pub fn filter<'d>(&self, devices: &'d [Device]) -> Vec<&'d Device> {
+		let mut matching: Vec<&Device> = Vec::new();
+		for device in devices.iter().filter(|device| self.matches(device)) {
+			match matching
+				.iter_mut()
+				.find(|existing| existing.device_identifier == device.device_identifier)
+			{
+				Some(existing) => {
+					if existing.interface != DeviceInterface::Usb && device.interface == DeviceInterface::Usb
+					{
+						*existing = device;
+					}
+				}
+				None => matching.push(device),
+			}
+		}
+		matching
+	}
+
+	/// Selects exactly one device, erroring with the candidates
+	/// if none or more than one device matches.
+	pub fn select<'d>(&self, devices: &'d [Device]) -> Result<&'d Device> {
+		let candidates = || {
+			devices
+				.iter()
+				.map(ToString::to_string)
+				.collect::<Vec<_>>()
+				.join(", ")
+		};
+		match self.filter(devices).as_slice() {
+			[device] => Ok(device),
+			[] => Err(Error::NoMatchingDevice {
+				filter: self.to_string(),
+				candidates: candidates(),
+			}),
+			matching => Err(Error::AmbiguousDevice {
+				filter: self.to_string(),
+				candidates: matching
+					.iter()
+					.map(ToString::to_string)
+					.collect::<Vec<_>>()
+					.join(", "),
+			}),
+		}
+	}
+}
+
+impl std::fmt::Display for DeviceFilter {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut criteria = Vec::new();
+		if let Some(name) = &self.name {
+			criteria.push(format!("name {:?}", name));
+		}
+		if let Some(identifier) = &self.identifier {
+			criteria.push(format!("identifier {:?}", identifier));
+		}
+		if let Some(model) = &self.model {
+			criteria.push(format!("model {:?}", model.to_string()));
+		}
+		if let Some(interface) = &self.interface {
+			criteria.push(format!("interface {}", interface));
+		}
+		match criteria.is_empty() {
+			true => write!(f, "any device"),
+			false => write!(f, "{}", criteria.join(" and ")),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn device(identifier: &str, name: &str, model: &str, interface: DeviceInterface) -> Device {
+		Device {
+			device_identifier: identifier.into(),
+			device_name: name.into(),
+			model_name: model.parse().unwrap(),
+			interface,
+		}
+	}
+
+	#[test]
+	fn selects_devices() {
+		let devices = [
+			device("a", "Lab iPhone", "iPhone 12", DeviceInterface::Wifi),
+			device("a", "Lab iPhone", "iPhone 12", DeviceInterface::Usb),
+			device(
+				"b",
+				"Lab iPad",
+				"iPad Air (5th generation)",
+				DeviceInterface::Usb,
+			),
+			device("c", "Spare iPhone", "iPhone 12", DeviceInterface::Wifi),
+		];
+
+		let selected = DeviceFilter::default()
+			.with_name("lab iphone")
+			.select(&devices)
+			.unwrap();
+		assert_eq!(selected, &devices[1]);
+
+		let selected = DeviceFilter::default()
+			.with_identifier("c")
+			.select(&devices)
+			.unwrap();
+		assert_eq!(selected, &devices[3]);
+
+		let selected = DeviceFilter::default()
+			.with_name("Lab iPhone")
+			.with_interface(DeviceInterface::Wifi)
+			.select(&devices)
+			.unwrap();
+		assert_eq!(selected, &devices[0]);
+
+		let ambiguous = DeviceFilter::default()
+			.with_model("iPhone 12".parse().unwrap())
+			.select(&devices);
+		assert!(matches!(ambiguous, Err(Error::AmbiguousDevice { .. })));
+
+		let missing = DeviceFilter::default()
+			.with_name("Other iPhone")
+			.select(&devices);
+		assert!(matches!(missing, Err(Error::NoMatchingDevice { .. })));
+
+		assert!(DeviceFilter::default().select(&[]).is_err());
+	}
+}
@@ -0,0 +1,652 @@
+//! Parses the stream of concatenated JSON objects `ios-deploy --json` prints.
+//!
+//! `ios-deploy` doesn't print a JSON array, or even newline delimited JSON:
+//! objects are printed back to back, sometimes pretty printed across lines,
+//! and interleaved with the odd non-JSON line (e.g. from `lldb`).
+
+use std::{
+	collections::VecDeque,
+	io::{BufReader, Read, Write},
+	process::{Child, ChildStdout, ExitStatus, Stdio},
+	thread::JoinHandle,
+};
+
+use crate::prelude::*;
+
+use super::detect::{Device, DeviceInterface};
+
+/// A piece of `ios-deploy` output, see [JsonObjectSplitter::push]
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputChunk {
+	/// A complete top level JSON object
+	Object(String),
+
+	/// Any other output, e.g. from `lldb` or the launched app
+	Text(String),
+}
+
+/// Incrementally splits text into top level JSON objects,
+/// tracking brace depth and string literals so that `}{` inside a string
+/// doesn't split an object.
+///
+/// An object only starts with a `{` at the start of a line,
+/// or directly after the previous object, so e.g. app output
+/// like `loaded {count: 2}` stays [OutputChunk::Text]
+#[derive(Debug)]
+pub struct JsonObjectSplitter {
+	buffer: String,
+	text: String,
+	depth: usize,
+	in_string: bool,
+	escaped: bool,
+	/// Only whitespace since the last newline or object
+	line_start: bool,
+}
+
+impl Default for JsonObjectSplitter {
+	fn default() -> Self {
+		Self {
+			buffer: String::new(),
+			text: String::new(),
+			depth: 0,
+			in_string: false,
+			escaped: false,
+			line_start: true,
+		}
+	}
+}
+
+impl JsonObjectSplitter {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Feeds more text into the splitter, returning every object completed by it
+	/// and the text between them.
+	/// Partial objects are kept until a later call completes them,
+	/// partial lines of text are returned straight away, e.g. for an `(lldb)` prompt.
+	/// Whitespace only text is dropped
+	pub fn push(&mut self, text: &str) -> Vec<OutputChunk> {
+		let mut chunks = Vec::new();
+		for c in text.chars() {
+			if self.depth == 0 {
+				if c == '{' && self.line_start {
+					self.flush_text(&mut chunks);
+					self.buffer.push(c);
+					self.depth = 1;
+				} else {
+					self.text.push(c);
+					if c == '\n' {
+						self.line_start = true;
+					} else if !c.is_whitespace() {
+						self.line_start = false;
+					}
+				}
+				continue;
+			}
+
+			self.buffer.push(c);
+			if self.in_string {
+				match c {
+					_ if self.escaped => self.escaped = false,
+					'\\' => self.escaped = true,
+					'"' => self.in_string = false,
+					_ => {}
+				}
+				continue;
+			}
+			match c {
+				'"' => self.in_string = true,
+				'{' => self.depth += 1,
+				'}' => {
+					self.depth -= 1;
+					if self.depth == 0 {
+						chunks.push(OutputChunk::Object(std::mem::take(&mut self.buffer)));
+						self.line_start = true;
+					}
+				}
+				_ => {}
+			}
+		}
+		self.flush_text(&mut chunks);
+		chunks
+	}
+
+	fn flush_text(&mut self, chunks: &mut Vec<OutputChunk>) {
+		let text = std::mem::take(&mut self.text);
+		if !text.trim().is_empty() {
+			chunks.push(OutputChunk::Text(text));
+		}
+	}
+
+	/// Text of an object that was started but never closed, if any.
+	pub fn remainder(&self) -> Option<&str> {
+		if self.buffer.is_empty() {
+			None
+		} else {
+			Some(&self.buffer)
+		}
+	}
+
+	/// Gives up on the object currently being read, e.g. if a line of app output
+	/// started with an unbalanced `{`.
+	/// Its first line is returned as [OutputChunk::Text], and the text after it is
+	/// split again, so objects that followed the unbalanced line aren't lost
+	pub fn abandon(&mut self) -> Vec<OutputChunk> {
+		let abandoned = std::mem::take(&mut self.buffer);
+		self.depth = 0;
+		self.in_string = false;
+		self.escaped = false;
+		if abandoned.is_empty() {
+			return Vec::new();
+		}
+		let (first_line, rest) = match abandoned.find('\n') {
+			Some(newline) => abandoned.split_at(newline + 1),
+			None => (abandoned.as_str(), ""),
+		};
+		self.line_start = first_line.ends_with('\n');
+		let mut chunks = vec![OutputChunk::Text(first_line.to_owned())];
+		chunks.extend(self.push(rest));
+		chunks
+	}
+}
+
+/// Splits complete output into its top level JSON objects, skipping any other text.
+/// See [JsonObjectSplitter].
+pub fn split_json_objects(output: &str) -> Vec<String> {
+	let mut splitter = JsonObjectSplitter::new();
+	let objects = splitter
+		.push(output)
+		.into_iter()
+		.filter_map(|chunk| match chunk {
+			OutputChunk::Object(object) => Some(object),
+			OutputChunk::Text(text) => {
+				trace!(?text, "Skipping text outside of a JSON object");
+				None
+			}
+		})
+		.collect();
+	if let Some(remainder) = splitter.remainder() {
+		warn!(%remainder, "ios-deploy output ended inside an unterminated JSON object");
+	}
+	objects
+}
+
+/// A typed `ios-deploy --json` event, discriminated by its `Event` key.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event")]
+#[non_exhaustive]
+pub enum Event {
+	DeviceDetected(DeviceDetected),
+
+	/// Progress copying the bundle to the device
+	BundleCopy(BundleProgress),
+
+	/// Progress installing the copied bundle.
+	/// Also parsed from `Install` events
+	BundleInstall(BundleProgress),
+
+	Error(ErrorEvent),
+
+	/// Any event kind not (yet) parsed into its own variant,
+	/// or a known kind whose fields didn't match.
+	Other {
+		kind: Option<String>,
+		raw: serde_json::Value,
+	},
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceDetected {
+	#[serde(rename = "Interface")]
+	pub interface: DeviceInterface,
+
+	#[serde(rename = "Device")]
+	pub device: DetectedDevice,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedDevice {
+	#[serde(rename = "DeviceIdentifier")]
+	pub device_identifier: String,
+
+	#[serde(rename = "DeviceName")]
+	pub device_name: String,
+
+	#[serde(rename = "modelName")]
+	pub model_name: ModelName,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleProgress {
+	#[serde(rename = "OverallPercent", default)]
+	pub overall_percent: Option<f64>,
+
+	#[serde(rename = "Percent", default)]
+	pub percent: Option<f64>,
+
+	/// e.g. "CopyingFile" or "Complete"
+	#[serde(rename = "Status", default)]
+	pub status: Option<String>,
+
+	/// The file currently being copied
+	#[serde(rename = "Path", default)]
+	pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorEvent {
+	#[serde(rename = "Code", default)]
+	pub code: Option<i64>,
+
+	#[serde(rename = "Status", default)]
+	pub status: Option<String>,
+}
+
+impl From<DeviceDetected> for Device {
+	fn from(event: DeviceDetected) -> Self {
+		Device {
+			device_identifier: event.device.device_identifier,
+			device_name: event.device.device_name,
+			model_name: event.device.model_name,
+			interface: event.interface,
+		}
+	}
+}
+
+impl Event {
+	/// Parses a single JSON object.
+	/// Only fails if `object` isn't valid JSON,
+	/// unexpected shapes become [Event::Other].
+	pub fn from_json(object: &str) -> Result<Self> {
+		Ok(Self::from_value(serde_json::from_str(object)?))
+	}
+
+	pub fn from_value(raw: serde_json::Value) -> Self {
+		let kind = raw
+			.get("Event")
+			.and_then(|kind| kind.as_str())
+			.map(String::from);
+		let parsed = match kind.as_deref() {
+			Some("DeviceDetected") => serde_json::from_value(raw.clone()).map(Self::DeviceDetected),
+			Some("BundleCopy") => serde_json::from_value(raw.clone()).map(Self::BundleCopy),
+			Some("BundleInstall" | "Install") => {
+				serde_json::from_value(raw.clone()).map(Self::BundleInstall)
+			}
+			Some("Error") => serde_json::from_value(raw.clone()).map(Self::Error),
+			_ => {
+				trace!(?kind, "Unhandled ios-deploy event kind");
+				return Self::Other { kind, raw };
+			}
+		};
+		match parsed {
+			Ok(event) => event,
+			Err(err) => {
+				warn!(%err, %raw, "Failed to parse ios-deploy event");
+				Self::Other { kind, raw }
+			}
+		}
+	}
+
+	/// The `Event` key of the original JSON object.
+	pub fn kind(&self) -> Option<&str> {
+		match self {
+			Self::DeviceDetected(_) => Some("DeviceDetected"),
+			Self::BundleCopy(_) => Some("BundleCopy"),
+			Self::BundleInstall(_) => Some("BundleInstall"),
+			Self::Error(_) => Some("Error"),
+			Self::Other { kind, .. } => kind.as_deref(),
+		}
+	}
+}
+
+/// Parses every event in `output`, logging and skipping objects that aren't valid JSON.
+pub fn parse_events(output: &str) -> Vec<Event> {
+	split_json_objects(output)
+		.into_iter()
+		.filter_map(|object| match Event::from_json(&object) {
+			Ok(event) => Some(event),
+			Err(err) => {
+				warn!(%err, %object, "Skipping invalid JSON object in ios-deploy output");
+				None
+			}
+		})
+		.collect()
+}
+
+/// A running `ios-deploy --json` command.
+/// Iterate to receive each [Event] as it is printed,
+/// then call [EventStream::wait] for the exit status.
+/// The command is killed if dropped while still running.
+///
+/// Output that isn't an event, e.g. from `lldb` and the launched app,
+/// is written to this process's stderr when forwarding,
+/// and only logged otherwise
+#[derive(Debug)]
+pub struct EventStream {
+	child: Child,
+	stdout: BufReader<ChildStdout>,
+	/// Bytes read from stdout that aren't yet complete UTF-8
+	partial: Vec<u8>,
+	splitter: JsonObjectSplitter,
+	pending: VecDeque<OutputChunk>,
+	forward_output: bool,
+	stderr: Option<JoinHandle<std::io::Result<String>>>,
+}
+
+impl EventStream {
+	/// A started object longer than this is assumed to be unbalanced app output
+	const MAX_OBJECT_LEN: usize = 64 * 1024;
+
+	/// Spawns `command` with its stdout and stderr piped,
+	/// leaving stdin as configured.
+	/// With `forward_output`, stderr is also copied to this process's stderr as it is read
+	pub(super) fn spawn(mut command: std::process::Command, forward_output: bool) -> Result<Self> {
+		let mut child = command
+			.stdout(Stdio::piped())
+			.stderr(Stdio::piped())
+			.spawn()
+			.map_err(Error::ChildProcessIo)?;
+		let stdout = child.stdout.take().map(BufReader::new).ok_or_else(|| {
+			Error::ChildProcessIo(std::io::Error::other("ios-deploy stdout was not piped"))
+		})?;
+		// read on another thread, so a full stderr pipe can't block ios-deploy
+		let stderr = child.stderr.take().map(|mut stderr| {
+			std::thread::spawn(move || {
+				let mut output = Vec::new();
+				let mut buf = [0; 4096];
+				loop {
+					let read = stderr.read(&mut buf)?;
+					if read == 0 {
+						break;
+					}
+					if forward_output {
+						let mut ours = std::io::stderr();
+						ours.write_all(&buf[..read])?;
+						ours.flush()?;
+					}
+					output.extend_from_slice(&buf[..read]);
+				}
+				Ok(String::from_utf8_lossy(&output).into_owned())
+			})
+		});
+		Ok(Self {
+			child,
+			stdout,
+			partial: Vec::new(),
+			splitter: JsonObjectSplitter::new(),
+			pending: VecDeque::new(),
+			forward_output,
+			stderr,
+		})
+	}
+
+	/// Waits for `ios-deploy` to exit, returning its exit status and stderr.
+	/// Call once the iterator is exhausted, otherwise unread stdout is discarded
+	pub fn wait(&mut self) -> Result<(ExitStatus, String)> {
+		std::io::copy(&mut self.stdout, &mut std::io::sink()).map_err(Error::ChildProcessIo)?;
+		let status = self.child.wait().map_err(Error::ChildProcessIo)?;
+		let stderr = match self.stderr.take().map(JoinHandle::join) {
+			Some(Ok(stderr)) => stderr.map_err(Error::ChildProcessIo)?,
+			Some(Err(_)) => {
+				warn!("Thread reading ios-deploy stderr panicked");
+				String::new()
+			}
+			None => String::new(),
+		};
+		Ok((status, stderr))
+	}
+
+	fn output_text(&self, text: &str) {
+		match self.forward_output {
+			true => {
+				let mut stderr = std::io::stderr();
+				if let Err(err) = stderr
+					.write_all(text.as_bytes())
+					.and_then(|_| stderr.flush())
+				{
+					warn!(?err, "Failed to forward ios-deploy output");
+				}
+			}
+			false => debug!(?text, "ios-deploy output"),
+		}
+	}
+
+	/// Reads whatever stdout is available, splitting it into [Self::pending].
+	/// Returns `false` at the end of stdout
+	fn read_stdout(&mut self) -> std::io::Result<bool> {
+		let mut buf = [0; 4096];
+		let read = self.stdout.read(&mut buf)?;
+		if read == 0 {
+			if !self.partial.is_empty() {
+				let text = String::from_utf8_lossy(&std::mem::take(&mut self.partial)).into_owned();
+				self.pending.extend(self.splitter.push(&text));
+			}
+			// each abandon drops at least a line, so this ends
+			loop {
+				let chunks = self.splitter.abandon();
+				if chunks.is_empty() {
+					break;
+				}
+				self.pending.extend(chunks);
+			}
+			return Ok(!self.pending.is_empty());
+		}
+		self.partial.extend_from_slice(&buf[..read]);
+		// keep a multi-byte character split across reads until it is complete
+		let valid = match std::str::from_utf8(&self.partial) {
+			Ok(text) => text.len(),
+			Err(err) if err.error_len().is_none() => err.valid_up_to(),
+			Err(_) => self.partial.len(),
+		};
+		let bytes: Vec<u8> = self.partial.drain(..valid).collect();
+		let text = String::from_utf8_lossy(&bytes).into_owned();
+		self.pending.extend(self.splitter.push(&text));
+		if self
+			.splitter
+			.remainder()
+			.is_some_and(|remainder| remainder.len() > Self::MAX_OBJECT_LEN)
+		{
+			self.pending.extend(self.splitter.abandon());
+		}
+		Ok(true)
+	}
+}
+
+impl Iterator for EventStream {
+	type Item = Result<Event>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			match self.pending.pop_front() {
+				Some(OutputChunk::Object(object)) => {
+					match serde_json::from_str::<serde_json::Value>(&object) {
+						// every ios-deploy event has an `Event` key,
+						// anything else is likely JSON printed by the app
+						Ok(raw) if raw.get("Event").is_some() => return Some(Ok(Event::from_value(raw))),
+						_ => self.output_text(&object),
+					}
+				}
+				Some(OutputChunk::Text(text)) => self.output_text(&text),
+				None => match self.read_stdout() {
+					Ok(true) => {}
+					Ok(false) => return None,
+					Err(err) => return Some(Err(Error::ChildProcessIo(err))),
+				},
+			}
+		}
+	}
+}
+
+impl Drop for EventStream {
+	fn drop(&mut self) {
+		if let Ok(None) = self.child.try_wait() {
+			if let Err(err) = self
+				.child
+				.kill()
+				.and_then(|_| self.child.wait().map(|_| ()))
+			{
+				warn!(?err, "Failed to kill ios-deploy");
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const DETECTED: &str = r#"{
+	"Event": "DeviceDetected",
+	"Interface": "USB",
+	"Device": {
+		"DeviceIdentifier": "00008030-001A",
+		"DeviceName": "Lab }{ iPhone",
+		"modelName": "iPhone 12"
+	}
+}"#;
+
+	#[test]
+	fn splits_concatenated_objects() {
+		let output = format!("{DETECTED}{DETECTED}\n  {DETECTED}\n");
+		assert_eq!(split_json_objects(&output).len(), 3);
+	}
+
+	#[test]
+	fn splits_across_pushes() {
+		let mut splitter = JsonObjectSplitter::new();
+		let (start, end) = DETECTED.split_at(DETECTED.len() / 2);
+		assert!(splitter.push(start).is_empty());
+		assert_eq!(
+			splitter.push(end),
+			vec![OutputChunk::Object(DETECTED.to_string())]
+		);
+		assert_eq!(splitter.remainder(), None);
+	}
+
+	#[test]
+	fn keeps_app_output_as_text() {
+		let mut splitter = JsonObjectSplitter::new();
+		assert_eq!(
+			splitter.push("loaded {count: 2}\n(lldb) "),
+			vec![OutputChunk::Text("loaded {count: 2}\n(lldb) ".into())]
+		);
+
+		// an unbalanced `{` at the start of a line is given up on
+		assert!(splitter.push("\n{ unbalanced\n").is_empty());
+		assert_eq!(
+			splitter.abandon(),
+			vec![OutputChunk::Text("{ unbalanced\n".into())]
+		);
+		assert_eq!(
+			splitter.push(DETECTED),
+			vec![OutputChunk::Object(DETECTED.to_string())]
+		);
+	}
+
+	#[test]
+	fn keeps_objects_after_an_abandoned_line() {
+		let mut splitter = JsonObjectSplitter::new();
+		assert!(splitter
+			.push(&format!("{{ unbalanced\n{DETECTED}\n"))
+			.is_empty());
+		assert_eq!(
+			splitter.abandon(),
+			vec![
+				OutputChunk::Text("{ unbalanced\n".into()),
+				OutputChunk::Object(DETECTED.to_string()),
+			]
+		);
+		assert_eq!(splitter.remainder(), None);
+		assert!(splitter.abandon().is_empty());
+	}
+
+	#[test]
+	fn handles_escaped_quotes_and_stray_text() {
+		let output = format!(
+			"[....] Waiting for iOS device\n{{\"Event\":\"Other\",\"Status\":\"a \\\"}}\\\\\"}}garbage\n{DETECTED}"
+		);
+		let events = parse_events(&output);
+		assert_eq!(events.len(), 2);
+		assert_eq!(events[0].kind(), Some("Other"));
+		match &events[1] {
+			Event::DeviceDetected(detected) => {
+				assert_eq!(detected.device.device_name, "Lab }{ iPhone")
+			}
+			event => panic!("Expected a DeviceDetected event, got {:?}", event),
+		}
+	}
+
+	#[test]
+	fn unexpected_records_dont_fail() {
+		let output = format!(
+			r#"{{"Event":"DeviceDetected","Interface":"USB"}}{{"Event":"BundleCopy","Percent":10}}{{"no event":true}}{{not json}}{DETECTED}"#
+		);
+		let events = parse_events(&output);
+		let kinds: Vec<_> = events.iter().map(Event::kind).collect();
+		assert_eq!(
+			kinds,
+			vec![
+				Some("DeviceDetected"),
+				Some("BundleCopy"),
+				None,
+				Some("DeviceDetected")
+			]
+		);
+		assert!(matches!(events[0], Event::Other { .. }));
+		assert!(matches!(events[1], Event::BundleCopy(_)));
+		assert!(matches!(events[3], Event::DeviceDetected(_)));
+	}
+
+	#[test]
+	fn parses_progress_and_errors() {
+		let output = r#"
+{"Event":"BundleCopy","OverallPercent":10,"Percent":10,"Path":"Demo.app/Info.plist"}
+{"Event":"BundleInstall","OverallPercent":52.5,"Percent":5,"Status":"CreatingStagingDirectory"}
+{"Event":"Error","Code":-402653081,"Status":"The device is locked."}
+"#;
+		let events = parse_events(output);
+		match &events[..] {
+			[Event::BundleCopy(copy), Event::BundleInstall(install), Event::Error(error)] => {
+				assert_eq!(copy.path.as_deref(), Some("Demo.app/Info.plist"));
+				assert_eq!(install.overall_percent, Some(52.5));
+				assert_eq!(error.status.as_deref(), Some("The device is locked."));
+			}
+			events => panic!("Unexpected events {:?}", events),
+		}
+	}
+
+	#[test]
+	fn streams_events_from_a_child() {
+		let mut command = std::process::Command::new("/bin/sh");
+		command.args([
+			"-c",
+			r#"printf '{"Event":"BundleCopy",\n"OverallPercent":5}\nlldb noise {\n{"app":1}\n{"Event":"Error"}'; echo oops >&2; exit 3"#,
+		]);
+		let mut stream = EventStream::spawn(command, false).unwrap();
+		let events: Vec<_> = stream.by_ref().map(Result::unwrap).collect();
+		assert!(matches!(
+			events[..],
+			[Event::BundleCopy(_), Event::Error(_)]
+		));
+		let (status, stderr) = stream.wait().unwrap();
+		assert_eq!(status.code(), Some(3));
+		assert_eq!(stderr, "oops\n");
+	}
+
+	#[test]
+	fn streams_events_after_an_unbalanced_line() {
+		let mut command = std::process::Command::new("/bin/sh");
+		command.args([
+			"-c",
+			r#"printf '{ unbalanced\n{"Event":"BundleCopy","OverallPercent":5}\n{ again\n{"Event":"Error"}\n'"#,
+		]);
+		let mut stream = EventStream::spawn(command, false).unwrap();
+		let events: Vec<_> = stream.by_ref().map(Result::unwrap).collect();
+		assert!(matches!(
+			events[..],
+			[Event::BundleCopy(_), Event::Error(_)]
+		));
+		stream.wait().unwrap();
+	}
+}
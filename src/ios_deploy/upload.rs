@@ -1,26 +1,258 @@
 use crate::prelude::*;
 
-use super::{detect::Device, IosDeployCLIInstance};
+use std::process::Stdio;
+
+use super::{
+	detect::Device,
+	events::{BundleProgress, Event, EventStream},
+	IosDeployCLIInstance,
+};
 
 pub use output::*;
 mod output;
 
+/// Options passed to `ios-deploy` when installing and launching a bundle.
+/// The [Default] matches `ios-deploy --debug`, installing, launching and attaching `lldb`.
+/// [UploadConfig::debug] is `false` with `--no-debug`, only installing the bundle
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "cli", derive(clap::Args))]
+pub struct UploadConfig {
+	/// Only install the app, without launching it or attaching `lldb`
+	#[cfg_attr(
+		feature = "cli",
+		arg(long = "no-debug", action = clap::ArgAction::SetFalse)
+	)]
+	pub debug: bool,
+
+	/// Exits as soon as the app is launched, instead of waiting for it to exit
+	#[cfg_attr(feature = "cli", arg(long))]
+	pub justlaunch: bool,
+
+	/// Starts `lldb` in non-interactive mode, exiting when the app exits
+	#[cfg_attr(feature = "cli", arg(long))]
+	pub noninteractive: bool,
+
+	/// Installs and attaches `lldb`, but doesn't start the app
+	#[cfg_attr(feature = "cli", arg(long))]
+	pub nostart: bool,
+
+	/// Uninstalls the app before installing it
+	#[cfg_attr(feature = "cli", arg(long))]
+	pub uninstall: bool,
+
+	/// Arguments to launch the app with.
+	/// Can be passed multiple times.
+	///
+	/// `ios-deploy` takes these as one space separated string,
+	/// so an argument containing spaces is split into several
+	#[cfg_attr(feature = "cli", arg(long = "arg", allow_hyphen_values = true))]
+	pub args: Vec<String>,
+
+	/// Sets an environment variable when launching the app, as KEY=VALUE.
+	/// Can be passed multiple times.
+	///
+	/// `ios-deploy` takes these as one space separated string like the launch arguments,
+	/// so values can't contain spaces
+	#[cfg_attr(
+		feature = "cli",
+		arg(long = "env", value_parser = crate::cli::parse_env_var)
+	)]
+	pub envs: Vec<(String, String)>,
+
+	/// Overrides the bundle identifier used to find an already installed app
+	#[cfg_attr(feature = "cli", arg(long))]
+	pub bundle_id: Option<String>,
+
+	/// Ignores devices connected over Wi-Fi
+	#[cfg_attr(feature = "cli", arg(long))]
+	pub no_wifi: bool,
+
+	/// Seconds `ios-deploy` waits for the device to connect
+	#[cfg_attr(
+		feature = "cli",
+		arg(id = "upload_timeout", long = "upload-timeout", value_name = "SECONDS")
+	)]
+	pub timeout: Option<u16>,
+}
+
+impl Default for UploadConfig {
+	#[instrument(level = "trace", skip())]
+	fn default() -> Self {
+		UploadConfig {
+			debug: true,
+			justlaunch: false,
+			noninteractive: false,
+			nostart: false,
+			uninstall: false,
+			args: Vec::new(),
+			envs: Vec::new(),
+			bundle_id: None,
+			no_wifi: false,
+			timeout: None,
+		}
+	}
+}
+
+impl UploadConfig {
+	/// Only installs the bundle, without launching it or attaching `lldb`
+	pub fn install_only() -> Self {
+		UploadConfig {
+			debug: false,
+			..Default::default()
+		}
+	}
+
+	fn args(&self) -> Vec<String> {
+		let mut args = Vec::new();
+		let flags = [
+			(self.debug, "--debug"),
+			(self.justlaunch, "--justlaunch"),
+			(self.noninteractive, "--noninteractive"),
+			(self.nostart, "--nostart"),
+			(self.uninstall, "--uninstall"),
+			(self.no_wifi, "--no-wifi"),
+		];
+		for (_, flag) in flags.iter().filter(|(set, _)| *set) {
+			args.push(flag.to_string());
+		}
+		if !self.args.is_empty() {
+			args.extend(["--args".into(), self.args.join(" ")]);
+		}
+		if !self.envs.is_empty() {
+			let envs = self
+				.envs
+				.iter()
+				.map(|(key, value)| format!("{}={}", key, value))
+				.collect::<Vec<_>>()
+				.join(" ");
+			args.extend(["--envs".into(), envs]);
+		}
+		if let Some(bundle_id) = &self.bundle_id {
+			args.extend(["--bundle_id".into(), bundle_id.clone()]);
+		}
+		if let Some(timeout) = self.timeout {
+			args.extend(["--timeout".into(), timeout.to_string()]);
+		}
+		args
+	}
+}
+
+/// A [BundleProgress] event, reported while uploading
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum UploadProgress {
+	Copying(BundleProgress),
+	Installing(BundleProgress),
+}
+
+impl UploadProgress {
+	pub fn from_event(event: &Event) -> Option<Self> {
+		match event {
+			Event::BundleCopy(progress) => Some(Self::Copying(progress.clone())),
+			Event::BundleInstall(progress) => Some(Self::Installing(progress.clone())),
+			_ => None,
+		}
+	}
+
+	fn progress(&self) -> &BundleProgress {
+		match self {
+			Self::Copying(progress) | Self::Installing(progress) => progress,
+		}
+	}
+
+	/// Percent of the whole upload complete, from 0 to 100
+	pub fn overall_percent(&self) -> Option<f64> {
+		self.progress().overall_percent
+	}
+
+	/// The `ios-deploy` status, or the file being copied
+	pub fn status(&self) -> Option<&str> {
+		let progress = self.progress();
+		progress.status.as_deref().or(progress.path.as_deref())
+	}
+}
+
 impl IosDeployCLIInstance {
-	#[instrument(ret, skip_all)]
+	/// Uploads a bundle, see [IosDeployCLIInstance::upload_bundle_with_progress]
 	pub fn upload_bundle(
 		&self,
 		device: &Device,
 		bundle_path: impl AsRef<Utf8Path>,
+		config: &UploadConfig,
 	) -> Result<UploadOutput> {
-		let mut command = self
-			.bossy_command()
-			.with_args(["--id", &device.device_identifier])
-			.with_args(["--bundle", bundle_path.as_ref().as_str()]);
+		self.upload_bundle_with_progress(device, bundle_path, config, |_| {})
+	}
+
+	/// Uploads a bundle with `ios-deploy --json`,
+	/// calling `on_progress` with each progress event as it happens.
+	/// When [UploadConfig::debug] is set, stdin is inherited so `lldb` stays interactive,
+	/// and any output that isn't an event (from `lldb` and the app) is forwarded to stderr
+	#[instrument(ret, skip_all, fields(device = %device.device_identifier))]
+	pub fn upload_bundle_with_progress(
+		&self,
+		device: &Device,
+		bundle_path: impl AsRef<Utf8Path>,
+		config: &UploadConfig,
+		mut on_progress: impl FnMut(&UploadProgress),
+	) -> Result<UploadOutput> {
+		let mut command = self.std_command();
+		command
+			.args(["--id", &device.device_identifier])
+			.args(["--bundle", bundle_path.as_ref().as_str()])
+			.arg("--json")
+			.args(config.args())
+			.stdin(match config.debug {
+				true => Stdio::inherit(),
+				false => Stdio::null(),
+			});
+
+		let mut stream = EventStream::spawn(command, config.debug)?;
+		let mut events = Vec::new();
+		for event in stream.by_ref() {
+			let event = event?;
+			if let Some(progress) = UploadProgress::from_event(&event) {
+				on_progress(&progress);
+			}
+			events.push(event);
+		}
+		let (status, stderr) = stream.wait()?;
+		Ok(UploadOutput::from_events(status, events, stderr))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
 
-		// if self.debug {
-		command.add_arg("--debug");
-		// }
+	#[test]
+	fn upload_config_args() {
+		assert_eq!(UploadConfig::default().args(), vec!["--debug"]);
+		assert!(UploadConfig::install_only().args().is_empty());
 
-		UploadOutput::from_bossy_result(command.run_and_wait_for_output())
+		let config = UploadConfig {
+			justlaunch: true,
+			uninstall: true,
+			args: vec!["-v".into(), "--fast".into()],
+			envs: vec![("A".into(), "1".into()), ("B".into(), "2".into())],
+			bundle_id: Some("com.example.app".into()),
+			timeout: Some(5),
+			..Default::default()
+		};
+		assert_eq!(
+			config.args(),
+			vec![
+				"--debug",
+				"--justlaunch",
+				"--uninstall",
+				"--args",
+				"-v --fast",
+				"--envs",
+				"A=1 B=2",
+				"--bundle_id",
+				"com.example.app",
+				"--timeout",
+				"5"
+			]
+		);
 	}
 }
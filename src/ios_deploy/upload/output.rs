@@ -1,9 +1,40 @@
-use crate::prelude::*;
+use std::process::ExitStatus;
+
+use crate::{ios_deploy::events::Event, prelude::*};
 
 #[derive(Debug, Serialize)]
 #[non_exhaustive]
 #[must_use = include_doc!(must_use_cmd_output)]
 pub enum UploadOutput {
+	/// Every event `ios-deploy` printed while uploading
+	Uploaded { events: Vec<Event> },
+
+	/// The device is locked with a passcode, e.g.
+	/// `kAMDPasswordProtectedError` or "The device is locked"
+	ErrorDeviceLocked { message: String },
+
+	/// Developer Mode is disabled in the device's Settings, e.g. `DeveloperModeDisabled`
+	ErrorDeveloperModeDisabled { message: String },
+
+	/// The user hasn't trusted the app's developer on the device, e.g.
+	/// `0xe8000087 (... its profile has not been explicitly trusted by the user.)`
+	ErrorDeveloperNotTrusted { message: String },
+
+	/// The device hasn't trusted (paired with) this computer, e.g.
+	/// `kAMDInvalidPairRecordError` or `kAMDInvalidHostIDError`
+	ErrorDeviceNotTrusted { message: String },
+
+	/// The provisioning profile is missing or doesn't include the device, e.g.
+	/// `0xe8008015 (A valid provisioning profile for this executable was not found.)`
+	ErrorProvisioningProfile { message: String },
+
+	/// The app's code signature or entitlements are invalid, e.g.
+	/// `0xe800801c (No code signature found.)` or `ApplicationVerificationFailed`
+	ErrorInvalidSignature { message: String },
+
+	/// The device doesn't have enough free space for the app, e.g. `kAMDDiskFullError`
+	ErrorInsufficientStorage { message: String },
+
 	#[doc = include_doc!(cmd_error)]
 	ErrorUnImplemented { stderr: String },
 
@@ -11,6 +42,84 @@ pub enum UploadOutput {
 	SuccessUnImplemented { stdout: String },
 }
 
+impl UploadOutput {
+	pub(super) fn from_events(status: ExitStatus, events: Vec<Event>, stderr: String) -> Self {
+		if status.success() {
+			return Self::Uploaded { events };
+		}
+		// ios-deploy --json reports errors as events on stdout, as well as on stderr
+		let mut messages: Vec<String> = events
+			.iter()
+			.filter_map(|event| match event {
+				// the code is the MobileDevice error, e.g. 0xe8008015
+				Event::Error(error) => Some(match (error.code, &error.status) {
+					(Some(code), Some(status)) => format!("Error 0x{:x}: {}", code as u32, status),
+					(Some(code), None) => format!("Error 0x{:x}", code as u32),
+					(None, status) => status.clone()?,
+				}),
+				_ => None,
+			})
+			.collect();
+		if !stderr.trim().is_empty() {
+			messages.push(stderr.trim().to_owned());
+		}
+		Self::from_error_message(messages.join("\n"))
+	}
+
+	/// Classifies an `ios-deploy` error message into one of the known failures,
+	/// by the MobileDevice error codes and `kAMD*` error names it contains
+	fn from_error_message(message: String) -> Self {
+		let lowercase = message.to_lowercase();
+		let contains = |needles: &[&str]| {
+			needles
+				.iter()
+				.any(|needle| lowercase.contains(&needle.to_lowercase()))
+		};
+		let output = if contains(&["DeveloperModeDisabled"]) {
+			Self::ErrorDeveloperModeDisabled { message }
+		} else if contains(&["0xe8000087"]) {
+			Self::ErrorDeveloperNotTrusted { message }
+		} else if contains(&[
+			"kAMDPasswordProtectedError",
+			"0xe800001a",
+			"kAMDMobileImageMounterDeviceLocked",
+			"0xe80000e2",
+		]) {
+			Self::ErrorDeviceLocked { message }
+		} else if contains(&["0xe8008015", "0xe8008012"]) {
+			Self::ErrorProvisioningProfile { message }
+		} else if contains(&["ApplicationVerificationFailed", "0xe8008016", "0xe800801c"]) {
+			Self::ErrorInvalidSignature { message }
+		} else if contains(&[
+			"kAMDInvalidPairRecordError",
+			"kAMDMissingPairRecordError",
+			"kAMDInvalidHostIDError",
+			"kAMDMissingHostIDError",
+		]) {
+			Self::ErrorDeviceNotTrusted { message }
+		} else if contains(&["kAMDDiskFullError"]) {
+			Self::ErrorInsufficientStorage { message }
+		} else {
+			return Self::ErrorUnImplemented { stderr: message };
+		};
+		warn!(?output, "Parsed ios-deploy upload error");
+		output
+	}
+
+	/// Whether `ios-deploy` reported the install as complete
+	pub fn installed(&self) -> bool {
+		match self {
+			Self::Uploaded { events } => events.iter().any(|event| match event {
+				Event::BundleInstall(progress) => {
+					progress.status.as_deref() == Some("Complete") || progress.overall_percent == Some(100.0)
+				}
+				_ => false,
+			}),
+			_ => false,
+		}
+	}
+}
+
 impl CommandNomParsable for UploadOutput {
 	fn error_unimplemented(stderr: String) -> Self {
 		Self::ErrorUnImplemented { stderr }
@@ -19,15 +128,170 @@ impl CommandNomParsable for UploadOutput {
 	fn success_unimplemented(stdout: String) -> Self {
 		Self::SuccessUnImplemented { stdout }
 	}
+
+	fn errored_from_str(input: &str) -> Self {
+		Self::from_error_message(input.to_owned())
+	}
 }
 
 impl PublicCommandOutput for UploadOutput {
-	type PrimarySuccess = ();
+	type PrimarySuccess = Vec<Event>;
 
 	fn success(&self) -> Result<&Self::PrimarySuccess> {
 		match self {
-			UploadOutput::SuccessUnImplemented { .. } => Ok(&()),
+			UploadOutput::Uploaded { events } => Ok(events),
+			UploadOutput::SuccessUnImplemented { .. } => {
+				static NO_EVENTS: Vec<Event> = Vec::new();
+				Ok(&NO_EVENTS)
+			}
+			UploadOutput::ErrorDeviceLocked { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Unlock the device and keep it unlocked until the app is installed",
+			)),
+			UploadOutput::ErrorDeveloperModeDisabled { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Enable Developer Mode in Settings > Privacy & Security > Developer Mode, then restart the device",
+			)),
+			UploadOutput::ErrorDeveloperNotTrusted { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Trust the app's developer in Settings > General > VPN & Device Management, then try again",
+			)),
+			UploadOutput::ErrorDeviceNotTrusted { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Unlock the device, reconnect it over USB and tap \"Trust\" when asked to trust this computer",
+			)),
+			UploadOutput::ErrorProvisioningProfile { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Add the device's UDID to your provisioning profile (or enable automatic signing), then rebuild the app",
+			)),
+			UploadOutput::ErrorInvalidSignature { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Re-sign the app with a development certificate and profile for this device, and check its entitlements with `codesign -d --entitlements - <path>`",
+			)),
+			UploadOutput::ErrorInsufficientStorage { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Free up storage on the device, e.g. by deleting old builds, then try again",
+			)),
 			UploadOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::os::unix::process::ExitStatusExt;
+
+	use super::*;
+	use crate::ios_deploy::events::{BundleProgress, ErrorEvent};
+
+	#[test]
+	fn parses_errors() {
+		let cases = [
+			(
+				"[ !! ] Error 0xe80000e2: This device is no longer connected. kAMDPasswordProtectedError",
+				"ErrorDeviceLocked",
+			),
+			(
+				"[ !! ] kAMDMobileImageMounterDeveloperModeDisabled",
+				"ErrorDeveloperModeDisabled",
+			),
+			(
+				"[ !! ] Error 0xe800000a: kAMDInvalidPairRecordError",
+				"ErrorDeviceNotTrusted",
+			),
+			(
+				"[ !! ] Error 0xe8008015: A valid provisioning profile for this executable was not found. AMDeviceSecureInstallApplication(0, device, url, options, install_callback, 0)",
+				"ErrorProvisioningProfile",
+			),
+			(
+				"[ !! ] Error 0xe800801c: No code signature found. AMDeviceSecureInstallApplication(0, device, url, options, install_callback, 0)",
+				"ErrorInvalidSignature",
+			),
+			(
+				"[ !! ] Error 0xe8000087: Unable to launch com.example.app because it has an invalid code signature, inadequate entitlements or its profile has not been explicitly trusted by the user.",
+				"ErrorDeveloperNotTrusted",
+			),
+			(
+				"[ !! ] Could not pair with the device, kAMDInvalidHostIDError",
+				"ErrorDeviceNotTrusted",
+			),
+			("[ !! ] Error 0xe8000015: kAMDDiskFullError", "ErrorInsufficientStorage"),
+			("[ !! ] Unable to locate DeviceSupport directory", "ErrorUnImplemented"),
+			(
+				"[ !! ] Error 0xe8000001: the developer mode setting and signature were fine, but not enough storage",
+				"ErrorUnImplemented",
+			),
+		];
+		for (stderr, expected) in cases {
+			let output = UploadOutput::errored_from_str(stderr);
+			let variant = format!("{:?}", output);
+			assert!(variant.starts_with(expected), "{}: {}", stderr, variant);
+			assert!(output.failed());
+		}
+	}
+
+	fn error_event(code: Option<i64>, status: Option<&str>) -> Event {
+		Event::Error(ErrorEvent {
+			code,
+			status: status.map(str::to_owned),
+		})
+	}
+
+	fn bundle_install(status: &str, overall_percent: f64) -> Event {
+		Event::BundleInstall(BundleProgress {
+			overall_percent: Some(overall_percent),
+			percent: None,
+			status: Some(status.to_owned()),
+			path: None,
+		})
+	}
+
+	#[test]
+	fn from_events() {
+		let success = ExitStatus::from_raw(0);
+		let failure = ExitStatus::from_raw(1 << 8);
+
+		let output = UploadOutput::from_events(
+			success,
+			vec![error_event(Some(0xe8008015), Some("ignored"))],
+			String::new(),
+		);
+		assert!(matches!(output, UploadOutput::Uploaded { ref events } if events.len() == 1));
+
+		// the code is read from error events, which don't repeat it in their status
+		let output = UploadOutput::from_events(
+			failure,
+			vec![error_event(
+				Some(0xe8008015_u32 as i32 as i64),
+				Some("A valid provisioning profile for this executable was not found."),
+			)],
+			String::new(),
+		);
+		assert!(
+			matches!(output, UploadOutput::ErrorProvisioningProfile { ref message } if message.starts_with("Error 0xe8008015: "))
+		);
+
+		let output = UploadOutput::from_events(
+			failure,
+			vec![error_event(None, Some("Could not install the app"))],
+			"[ !! ] Error 0xe800001a: kAMDPasswordProtectedError\n".into(),
+		);
+		assert!(
+			matches!(output, UploadOutput::ErrorDeviceLocked { ref message } if message == "Could not install the app\n[ !! ] Error 0xe800001a: kAMDPasswordProtectedError")
+		);
+
+		let output = UploadOutput::from_events(failure, Vec::new(), "\n".into());
+		assert!(matches!(output, UploadOutput::ErrorUnImplemented { ref stderr } if stderr.is_empty()));
+	}
+
+	#[test]
+	fn installed() {
+		let uploaded =
+			|events| UploadOutput::from_events(ExitStatus::from_raw(0), events, String::new());
+		assert!(uploaded(vec![bundle_install("Complete", 100.0)]).installed());
+		assert!(uploaded(vec![bundle_install("InstallingEmbeddedProfile", 100.0)]).installed());
+		assert!(!uploaded(vec![bundle_install("CopyingFile", 40.0)]).installed());
+		assert!(!uploaded(Vec::new()).installed());
+		assert!(!UploadOutput::errored_from_str("[ !! ] Error 0xe8000087").installed());
+	}
+}
@@ -0,0 +1,222 @@
+use std::collections::VecDeque;
+
+use crate::prelude::*;
+
+use super::{
+	detect::{DetectDevicesConfig, Device},
+	IosDeployCLIInstance,
+};
+
+/// A device being connected or disconnected, as reported by [DeviceWatcher]
+/// between successive [IosDeployCLIInstance::detect_devices] results
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum DeviceEvent {
+	Connected { device: Device },
+	Disconnected { device: Device },
+}
+
+impl DeviceEvent {
+	pub fn device(&self) -> &Device {
+		match self {
+			DeviceEvent::Connected { device } | DeviceEvent::Disconnected { device } => device,
+		}
+	}
+}
+
+/// Devices are compared by identifier and interface,
+/// so moving a device from Wi-Fi to USB disconnects and reconnects it
+fn same_connection(a: &Device, b: &Device) -> bool {
+	a.device_identifier == b.device_identifier && a.interface == b.interface
+}
+
+/// Tracks connected devices across detections, only reporting a device as
+/// [DeviceEvent::Disconnected] once it is missing from several detections in a row,
+/// since `ios-deploy --detect` sometimes misses devices that are still connected
+#[derive(Debug)]
+struct DeviceTracker {
+	/// Connected devices, with how many detections in a row they have been missing from
+	connected: Vec<(Device, usize)>,
+	misses_before_disconnect: usize,
+}
+
+impl DeviceTracker {
+	fn new(misses_before_disconnect: usize) -> Self {
+		Self {
+			connected: Vec::new(),
+			misses_before_disconnect: misses_before_disconnect.max(1),
+		}
+	}
+
+	/// Disconnections, then connections, since the previous detection
+	fn update(&mut self, detected: &[Device]) -> Vec<DeviceEvent> {
+		let mut events = Vec::new();
+		self.connected.retain_mut(|(device, misses)| {
+			match detected.iter().find(|other| same_connection(device, other)) {
+				Some(other) => {
+					*device = other.clone();
+					*misses = 0;
+				}
+				None => *misses += 1,
+			}
+			let disconnected = *misses >= self.misses_before_disconnect;
+			if disconnected {
+				events.push(DeviceEvent::Disconnected {
+					device: device.clone(),
+				});
+			}
+			!disconnected
+		});
+		for device in detected {
+			if !self
+				.connected
+				.iter()
+				.any(|(other, _)| same_connection(device, other))
+			{
+				events.push(DeviceEvent::Connected {
+					device: device.clone(),
+				});
+				self.connected.push((device.clone(), 0));
+			}
+		}
+		events
+	}
+}
+
+/// Polls `ios-deploy --detect` back to back and yields the [DeviceEvent]s between runs.
+/// Never ends on its own, see [IosDeployCLIInstance::watch_devices]
+#[derive(Debug)]
+pub struct DeviceWatcher<'instance> {
+	ios_deploy: &'instance IosDeployCLIInstance,
+	config: DetectDevicesConfig,
+	tracker: DeviceTracker,
+	pending: VecDeque<DeviceEvent>,
+}
+
+impl DeviceWatcher<'_> {
+	/// How many detections in a row a device must be missing from before
+	/// it is reported as [DeviceEvent::Disconnected]
+	pub const DEFAULT_MISSES_BEFORE_DISCONNECT: usize = 2;
+
+	/// Overrides [DeviceWatcher::DEFAULT_MISSES_BEFORE_DISCONNECT], at least 1.
+	/// Higher values report disconnections later but more reliably
+	pub fn with_misses_before_disconnect(mut self, misses: usize) -> Self {
+		self.tracker.misses_before_disconnect = misses.max(1);
+		self
+	}
+}
+
+impl Iterator for DeviceWatcher<'_> {
+	type Item = Result<DeviceEvent>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some(event) = self.pending.pop_front() {
+				return Some(Ok(event));
+			}
+			// each detection waits for `config.timeout`, so there is no need to sleep
+			let devices = match self.ios_deploy.detect_devices(&self.config) {
+				Ok(devices) => devices,
+				Err(err) => return Some(Err(err)),
+			};
+			self.pending.extend(self.tracker.update(&devices));
+		}
+	}
+}
+
+impl IosDeployCLIInstance {
+	/// Watches for real devices being connected or disconnected.
+	/// This polls, running `ios-deploy --detect` back to back, each waiting `config.timeout` seconds,
+	/// so changes are noticed up to one timeout late.
+	/// Devices already connected are reported as [DeviceEvent::Connected] first,
+	/// and devices are only reported as [DeviceEvent::Disconnected] once missing from
+	/// [DeviceWatcher::DEFAULT_MISSES_BEFORE_DISCONNECT] detections in a row.
+	/// An [Err] from one detection doesn't end the watcher, the next call detects again
+	pub fn watch_devices(&self, config: DetectDevicesConfig) -> DeviceWatcher<'_> {
+		DeviceWatcher {
+			ios_deploy: self,
+			config,
+			tracker: DeviceTracker::new(DeviceWatcher::DEFAULT_MISSES_BEFORE_DISCONNECT),
+			pending: VecDeque::new(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ios_deploy::detect::DeviceInterface;
+
+	fn device(identifier: &str, interface: DeviceInterface) -> Device {
+		Device {
+			device_identifier: identifier.into(),
+			device_name: "Lab iPhone".into(),
+			model_name: "iPhone 12".parse().unwrap(),
+			interface,
+		}
+	}
+
+	#[test]
+	fn reports_changed_devices() {
+		let old = [
+			device("a", DeviceInterface::Usb),
+			device("b", DeviceInterface::Wifi),
+		];
+		let new = [
+			device("a", DeviceInterface::Usb),
+			device("b", DeviceInterface::Usb),
+			device("c", DeviceInterface::Usb),
+		];
+		let mut tracker = DeviceTracker::new(1);
+		assert_eq!(tracker.update(&old).len(), 2);
+		// moving a device from Wi-Fi to USB disconnects and reconnects it
+		assert_eq!(
+			tracker.update(&new),
+			vec![
+				DeviceEvent::Disconnected {
+					device: old[1].clone()
+				},
+				DeviceEvent::Connected {
+					device: new[1].clone()
+				},
+				DeviceEvent::Connected {
+					device: new[2].clone()
+				},
+			]
+		);
+		assert!(tracker.update(&new).is_empty());
+		assert_eq!(tracker.update(&[]).len(), 3);
+	}
+
+	#[test]
+	fn debounces_disconnections() {
+		let a = device("a", DeviceInterface::Usb);
+		let b = device("b", DeviceInterface::Wifi);
+		let only_a = [a.clone()];
+		let both = [a.clone(), b.clone()];
+		let mut tracker = DeviceTracker::new(2);
+
+		assert_eq!(tracker.update(&both).len(), 2);
+		// a single missed detection isn't a disconnection
+		assert!(tracker.update(&only_a).is_empty());
+		assert!(tracker.update(&both).is_empty());
+		assert!(tracker.update(&only_a).is_empty());
+		assert_eq!(
+			tracker.update(&only_a),
+			vec![DeviceEvent::Disconnected { device: b.clone() }]
+		);
+		assert!(tracker.update(&only_a).is_empty());
+		assert_eq!(
+			tracker.update(&both),
+			vec![DeviceEvent::Connected { device: b }]
+		);
+
+		let mut tracker = DeviceTracker::new(0);
+		tracker.update(&only_a);
+		assert_eq!(
+			tracker.update(&[]),
+			vec![DeviceEvent::Disconnected { device: a }]
+		);
+	}
+}
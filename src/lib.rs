@@ -2,6 +2,7 @@ pub mod prelude {
 	// public exports
 	pub use crate::error::Error;
 	pub use crate::shared::identifiers::*;
+	pub use crate::shared::ns_error::NSError;
 	pub use crate::{
 		codesign, error, ios_deploy, open, security, shared, shared::identifiers, shared::types, spctl,
 		xcrun, xcrun::simctl,
@@ -30,7 +31,7 @@ pub mod prelude {
 		branch::{alt, permutation},
 		bytes::complete::{tag, take_till, take_till1, take_until, take_while},
 		character::complete::{alpha0, alpha1, digit1, multispace0, multispace1, space0, space1},
-		combinator::{all_consuming, cut, map, map_res, opt, peek, rest, success, value},
+		combinator::{all_consuming, cut, map, map_opt, map_res, opt, peek, rest, success, value},
 		multi::fold_many1,
 		number::complete::float,
 		sequence::{delimited, pair, preceded, terminated, tuple},
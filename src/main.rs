@@ -63,7 +63,7 @@ fn main() {
 
 	trace!(config = ?config, "Parsed CLI arguments");
 
-	match apple_clis::cli::run::run(config.command) {
+	match apple_clis::cli::run::run(&config.args, config.command) {
 		Ok(report) => {
 			if let Some(report) = report {
 				let value = serde_json::ser::to_string_pretty(&report).expect("Couldn't pretty print JSON");
@@ -1,6 +1,7 @@
 use crate::prelude::*;
 
 pub mod identifiers;
+pub mod ns_error;
 pub mod types;
 
 pub(crate) use traits::impl_from_str_nom;
@@ -10,11 +11,11 @@ mod traits;
 
 /// A combinator that takes a parser `inner` and produces a parser that also consumes both leading and
 /// trailing whitespace, returning the output of `inner`.
-pub(crate) fn ws<'a, F: 'a, O, E: nom::error::ParseError<&'a str>>(
+pub(crate) fn ws<'a, F, O, E: nom::error::ParseError<&'a str>>(
 	inner: F,
 ) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
 where
-	F: Fn(&'a str) -> IResult<&'a str, O, E>,
+	F: 'a + Fn(&'a str) -> IResult<&'a str, O, E>,
 {
 	delimited(multispace0, inner, multispace0)
 }
@@ -28,7 +29,7 @@ where
 /// assert_nom_parses<YourT>(examples, func);
 /// ```
 #[cfg(any(test, doctest))]
-fn assert_nom_parses<T: NomFromStr + std::fmt::Display + std::fmt::Debug>(
+pub(crate) fn assert_nom_parses<T: NomFromStr + std::fmt::Display + std::fmt::Debug>(
 	examples: impl IntoIterator<Item = &'static str>,
 	successfully_parsed: impl Fn(&T) -> bool,
 ) {
@@ -11,5 +11,11 @@ mod model_name;
 mod screen_size;
 
 /// e.g. "com.apple.CoreSimulator.SimRuntime.iOS-16-4"
-#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq, derive_more::Display)]
 pub struct RuntimeIdentifier(String);
+
+impl RuntimeIdentifier {
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+}
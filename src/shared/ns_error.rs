@@ -0,0 +1,205 @@
+use crate::prelude::*;
+
+/// The structured error `simctl` (and other Apple tools) print when an `NSError` occurs:
+/// ```text
+/// An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=60):
+/// Unable to boot the Simulator.
+/// launchd failed to respond.
+/// Underlying error (domain=com.apple.SimLaunchHostService.RequestError, code=4):
+///         Failed to start launchd_sim: could not bind to session, launchd_sim may have crashed or quit responding
+/// ```
+///
+/// Prefer matching on [NSError::domain] and [NSError::code] over the message,
+/// since the message is localized and changes between Xcode versions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NSError {
+	pub domain: String,
+	pub code: i64,
+	/// Human readable message, with each line trimmed and joined with `\n`
+	pub message: String,
+	pub underlying: Option<Box<NSError>>,
+}
+
+impl_from_str_nom!(NSError);
+
+impl NSError {
+	/// Whether this error (not including its underlying errors) has the given domain and code
+	pub fn is(&self, domain: &str, code: i64) -> bool {
+		self.domain == domain && self.code == code
+	}
+
+	/// Iterates over this error and then all of its underlying errors, outermost first
+	pub fn chain(&self) -> impl Iterator<Item = &NSError> {
+		std::iter::successors(Some(self), |error| error.underlying.as_deref())
+	}
+
+	/// Whether this error or any of its underlying errors has the given domain and code
+	pub fn any_is(&self, domain: &str, code: i64) -> bool {
+		self.chain().any(|error| error.is(domain, code))
+	}
+
+	/// Whether this error or any of its underlying errors has the given domain
+	pub fn any_domain(&self, domain: &str) -> bool {
+		self.chain().any(|error| error.domain == domain)
+	}
+
+	/// Whether the message of this error or any of its underlying errors contains `needle`.
+	/// Prefer [NSError::any_is] where the domain and code are distinctive enough.
+	pub fn any_message_contains(&self, needle: &str) -> bool {
+		self.chain().any(|error| error.message.contains(needle))
+	}
+
+	/// For `com.apple.CoreSimulator.SimError` code 405, the generic "invalid state" error,
+	/// the state the simulator was in, e.g. `Booted` or `Shutting Down`:
+	/// ```text
+	/// An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):
+	/// Unable to boot device in current state: Booted
+	/// ```
+	pub fn invalid_state(&self) -> Option<&str> {
+		if !self.is("com.apple.CoreSimulator.SimError", 405) {
+			return None;
+		}
+		self
+			.message
+			.lines()
+			.find_map(|line| line.split_once("current state:"))
+			.map(|(_, state)| state.trim())
+	}
+
+	fn fmt_indented(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+		let indent = "\t".repeat(depth);
+		for line in self.message.lines() {
+			write!(f, "\n{}{}", indent, line)?;
+		}
+		if let Some(underlying) = self.underlying.as_deref() {
+			write!(
+				f,
+				"\n{}Underlying error (domain={}, code={}):",
+				indent, underlying.domain, underlying.code
+			)?;
+			underlying.fmt_indented(f, depth + 1)?;
+		}
+		Ok(())
+	}
+}
+
+impl Display for NSError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"An error was encountered processing the command (domain={}, code={}):",
+			self.domain, self.code
+		)?;
+		self.fmt_indented(f, 0)
+	}
+}
+
+/// Parses `(domain=NSPOSIXErrorDomain, code=60):`
+fn domain_and_code(input: &str) -> IResult<&str, (&str, i64)> {
+	delimited(
+		tag("(domain="),
+		pair(
+			take_till(|c| c == ','),
+			preceded(
+				ws(tag(",")),
+				preceded(
+					tag("code="),
+					map_res(
+						pair(opt(tag("-")), digit1),
+						|(sign, digits): (Option<&str>, &str)| {
+							format!("{}{}", sign.unwrap_or_default(), digits).parse::<i64>()
+						},
+					),
+				),
+			),
+		),
+		tag("):"),
+	)(input)
+}
+
+/// Consumes message lines up until the next `Underlying error` header, or the end of input
+fn message(input: &str) -> IResult<&str, String> {
+	map(
+		alt((take_until("Underlying error ("), rest)),
+		|message: &str| {
+			message
+				.lines()
+				.map(str::trim)
+				.filter(|line| !line.is_empty())
+				.collect::<Vec<_>>()
+				.join("\n")
+		},
+	)(input)
+}
+
+fn underlying(input: &str) -> IResult<&str, NSError> {
+	let (remaining, (domain, code)) = preceded(ws(tag("Underlying error")), domain_and_code)(input)?;
+	let (remaining, message) = message(remaining)?;
+	let (remaining, underlying) = opt(underlying)(remaining)?;
+	Ok((
+		remaining,
+		NSError {
+			domain: domain.to_owned(),
+			code,
+			message,
+			underlying: underlying.map(Box::new),
+		},
+	))
+}
+
+impl NomFromStr for NSError {
+	#[tracing::instrument(level = "trace", skip(input))]
+	fn nom_from_str(input: &str) -> IResult<&str, Self> {
+		let (remaining, (domain, code)) = preceded(
+			ws(tag("An error was encountered processing the command")),
+			domain_and_code,
+		)(input)?;
+		let (remaining, message) = message(remaining)?;
+		let (remaining, underlying) = opt(underlying)(remaining)?;
+		Ok((
+			remaining,
+			NSError {
+				domain: domain.to_owned(),
+				code,
+				message,
+				underlying: underlying.map(Box::new),
+			},
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::shared::assert_nom_parses;
+
+	use super::*;
+
+	#[test]
+	fn ns_error_hardcoded() {
+		let examples = [
+			"An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):\nUnable to boot device in current state: Booted",
+			"An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=60):\nUnable to boot the Simulator.\nlaunchd failed to respond.\nUnderlying error (domain=com.apple.SimLaunchHostService.RequestError, code=4):\n\tFailed to start launchd_sim: could not bind to session, launchd_sim may have crashed or quit responding",
+			"An error was encountered processing the command (domain=NSOSStatusErrorDomain, code=-10814):\nSimulator device failed to open example://.\nUnderlying error (domain=NSOSStatusErrorDomain, code=-10814):\n\tThe operation couldn’t be completed.\n\tUnderlying error (domain=FBSOpenApplicationErrorDomain, code=1):\n\t\tNo application was found",
+		];
+		assert_nom_parses::<NSError>(examples, |_| true);
+	}
+
+	#[test]
+	fn ns_error_chain() {
+		let error: NSError = "An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=60):
+Unable to boot the Simulator.
+launchd failed to respond.
+Underlying error (domain=com.apple.SimLaunchHostService.RequestError, code=4):
+	Failed to start launchd_sim: could not bind to session, launchd_sim may have crashed or quit responding
+"
+		.parse()
+		.unwrap();
+		assert!(error.is("NSPOSIXErrorDomain", 60));
+		assert_eq!(
+			error.message,
+			"Unable to boot the Simulator.\nlaunchd failed to respond."
+		);
+		assert!(error.any_is("com.apple.SimLaunchHostService.RequestError", 4));
+		assert_eq!(error.chain().count(), 2);
+	}
+}
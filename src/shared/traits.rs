@@ -186,6 +186,9 @@ pub(crate) use impl_exec_instance;
 
 macro_rules! impl_exec_child {
 	($t:ty, parent = $parent:ty, subcommand = $name:expr) => {
+		impl_exec_child!($t, parent = $parent, subcommand = $name, defaults = {});
+	};
+	($t:ty, parent = $parent:ty, subcommand = $name:expr, defaults = { $($field:ident: $default:expr),* $(,)? }) => {
 		impl<'src> $crate::shared::ExecChild<'src> for $t {
 			const SUBCOMMAND_NAME: &'static str = $name;
 			type Parent = $parent;
@@ -193,6 +196,7 @@ macro_rules! impl_exec_child {
 			unsafe fn new_unchecked(parent: &'src Self::Parent) -> Self {
 				Self {
 					exec_parent: parent,
+					$($field: $default,)*
 				}
 			}
 
@@ -224,6 +228,13 @@ impl NomFromStr for NonZeroU8 {
 	}
 }
 
+impl NomFromStr for u32 {
+	#[tracing::instrument(level = "trace", skip(input))]
+	fn nom_from_str(input: &str) -> IResult<&str, Self> {
+		map_res(digit1, |s: &str| s.parse())(input)
+	}
+}
+
 /// Common operations that should be implemented on all command outputs.
 /// Often the 'success' of a command is a semantics issue, and is hence
 /// not by default implemented as a plain [std::result::Result].
@@ -2,31 +2,79 @@ use crate::prelude::*;
 
 use super::XcRunInstance;
 
+pub mod add_media;
 pub mod boot;
+pub mod create;
+pub mod delete;
+pub mod device_set;
+pub mod diagnostics;
+pub mod ensure;
 pub mod install;
+pub mod io;
+pub mod keychain;
 pub mod launch;
 pub mod list;
+pub mod location;
+pub mod log;
+pub mod open_url;
+pub mod pasteboard;
+pub mod privacy;
+pub mod push;
+pub mod runtime;
+pub mod shutdown;
+pub mod spawn;
+pub mod status_bar;
+pub mod ui;
+pub mod watch;
 
 #[derive(Debug)]
 pub struct XcRunSimctlInstance<'src> {
 	exec_parent: &'src XcRunInstance,
+	/// See [XcRunSimctlInstance::with_device_set]
+	device_set: Option<Utf8PathBuf>,
 }
 
 impl_exec_child!(
 	XcRunSimctlInstance<'src>,
 	parent = XcRunInstance,
-	subcommand = "simctl"
+	subcommand = "simctl",
+	defaults = { device_set: None }
 );
 
 impl XcRunInstance {
 	/// Instantiates an xcrun simctl CLI instance
-	pub fn simctl(&self) -> XcRunSimctlInstance {
-		XcRunSimctlInstance { exec_parent: self }
+	pub fn simctl(&self) -> XcRunSimctlInstance<'_> {
+		XcRunSimctlInstance {
+			exec_parent: self,
+			device_set: None,
+		}
 	}
 }
 
 impl XcRunSimctlInstance<'_> {
 	fn bossy_command(&self) -> bossy::Command {
-		self.exec_parent.bossy_command().with_arg("simctl")
+		let command = self.exec_parent.bossy_command().with_arg("simctl");
+		match &self.device_set {
+			Some(device_set) => command.with_arg("--set").with_arg(device_set),
+			None => command,
+		}
 	}
+
+	/// For long running processes that need their PID, which [bossy::Handle] doesn't expose.
+	/// Clears the environment like [bossy::Command::pure]
+	fn std_command(&self) -> std::process::Command {
+		let mut command = std::process::Command::new(self.exec_parent.get_inner_exec_path());
+		command.env_clear().arg("simctl");
+		if let Some(device_set) = &self.device_set {
+			command.arg("--set").arg(device_set);
+		}
+		command
+	}
+}
+
+/// An `xcrun` that is actually `echo`, so tests can check the arguments passed to `simctl`
+#[cfg(test)]
+pub(crate) fn echo_xcrun() -> XcRunInstance {
+	// Safety: `/bin/echo` is a valid executable
+	unsafe { XcRunInstance::new_unchecked("/bin/echo") }
 }
@@ -0,0 +1,48 @@
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+
+impl XcRunSimctlInstance<'_> {
+	/// Adds photos, videos or contacts to the simulator.
+	/// Images and videos are added to the Photos library, `.vcf` files to Contacts
+	#[instrument(skip_all, ret, fields(%device_name))]
+	pub fn add_media(
+		&self,
+		device_name: &DeviceName,
+		paths: impl IntoIterator<Item = impl AsRef<Utf8Path>>,
+	) -> Result<AddMediaOutput> {
+		AddMediaOutput::from_bossy_result(
+			self
+				.bossy_command()
+				.with_arg("addmedia")
+				.with_arg(device_name.to_string())
+				.with_args(paths.into_iter().map(|path| path.as_ref().to_owned()))
+				.run_and_wait_for_output(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn add_media_args() {
+		let xcrun = super::super::echo_xcrun();
+		let device_name = DeviceName::from_str("iPhone 15 Pro").unwrap();
+		let output = xcrun
+			.simctl()
+			.add_media(&device_name, ["photo.png", "contact.vcf"])
+			.unwrap();
+		match output {
+			AddMediaOutput::SuccessUnImplemented { stdout } => assert_eq!(
+				stdout,
+				"simctl addmedia iPhone 15 Pro photo.png contact.vcf\n"
+			),
+			output => panic!("Unexpected output {:?}", output),
+		}
+	}
+}
@@ -0,0 +1,91 @@
+use crate::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum AddMediaOutput {
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	/// One of the paths doesn't exist:
+	/// ```text
+	/// An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=2):
+	/// Failed to add media
+	/// ```
+	ErrorMediaNotFound { error: NSError },
+
+	/// One of the files isn't an image, video or contact:
+	/// ```text
+	/// An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=22):
+	/// Unsupported file type
+	/// ```
+	ErrorUnsupportedMedia { error: NSError },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for AddMediaOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn errored_nom_from_str(input: &str) -> IResult<&str, Self> {
+		map_opt(NSError::nom_from_str, |error| {
+			let output = if error.any_is("NSPOSIXErrorDomain", 2) {
+				Self::ErrorMediaNotFound { error }
+			} else if error.any_message_contains("Unsupported file type") {
+				Self::ErrorUnsupportedMedia { error }
+			} else {
+				return None;
+			};
+			warn!(?output, "Parsed xcrun simctl addmedia error");
+			Some(output)
+		})(input)
+	}
+}
+
+impl PublicCommandOutput for AddMediaOutput {
+	type PrimarySuccess = ();
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			AddMediaOutput::SuccessUnImplemented { .. } => Ok(&()),
+			AddMediaOutput::ErrorMediaNotFound { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Check that every media path exists",
+			)),
+			AddMediaOutput::ErrorUnsupportedMedia { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Only images, videos and contacts (.vcf) are supported",
+			)),
+			AddMediaOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn add_media_output() {
+		assert!(AddMediaOutput::success_from_str("").success().is_ok());
+
+		let hint = |stderr: &str| match AddMediaOutput::errored_from_str(stderr).success() {
+			Err(Error::OutputErrored { help_hint, .. }) => help_hint,
+			result => panic!("Expected an error, got {:?}", result),
+		};
+		assert!(hint("An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=2):\nFailed to add media\n")
+			.unwrap()
+			.contains("exists"));
+		assert!(hint("An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=22):\nUnsupported file type\n")
+			.unwrap()
+			.contains(".vcf"));
+		assert_eq!(hint("Invalid device: booted\n"), None);
+	}
+}
@@ -5,6 +5,11 @@ use crate::prelude::*;
 #[must_use = include_doc!(must_use_cmd_output)]
 pub enum BootOutput {
 	/// NOT considered an error case, since the simulator is *already* booted.
+	/// ```text
+	/// An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):
+	/// Unable to boot device in current state: Booted
+	/// ```
+	/// Other states, e.g. `Shutting Down`, are errors
 	AlreadyBooted,
 
 	#[doc = include_doc!(cmd_success)]
@@ -23,7 +28,7 @@ pub enum BootOutput {
 	/// launchd failed to respond.
 	/// Underlying error (domain=com.apple.SimLaunchHostService.RequestError, code=4):
 	///         Failed to start launchd_sim: could not bind to session, launchd_sim may have crashed or quit responding
-	ErrorLaunchDFailed { stderr: String },
+	ErrorLaunchDFailed { error: NSError },
 }
 
 impl CommandNomParsable for BootOutput {
@@ -36,7 +41,7 @@ impl CommandNomParsable for BootOutput {
 	}
 
 	fn errored_nom_from_str(input: &str) -> IResult<&str, Self> {
-		alt((parse_already_booted, parse_launchd_failed))(input)
+		parse_boot_error(input)
 	}
 }
 
@@ -49,50 +54,60 @@ impl PublicCommandOutput for BootOutput {
 			BootOutput::SuccessUnImplemented { .. } | BootOutput::AlreadyBooted => Ok(&()),
 			BootOutput::ErrorLaunchDFailed { .. } => Err(Error::output_errored_with_hint(
 				self,
-				"Try running `sudo rm -rf ~/Library/Developer/CoreSimulator/Caches` to fix this issue.",
+				"Try running `sudo rm -rf ~/Library/Developer/CoreSimulator/Caches` to fix this issue, or collect context with `apple-clis xcrun simctl diagnostics`.",
 			)),
 			BootOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
 		}
 	}
 }
 
-/// Parses [BootOutput::AlreadyBooted]
-fn parse_already_booted(input: &str) -> IResult<&str, BootOutput> {
-	let (remaining, _preamble) = ws(tag("An error was encountered processing the command"))(input)?;
-	let (remaining, domain) =
-		delimited(tag("(domain="), take_till(|c| c == ','), tag(","))(remaining)?;
-	let (remaining, error_code) = delimited(ws(tag("code=")), digit1, ws(tag("):")))(remaining)?;
-	let (_, msg) =
-		all_consuming(ws(tag("Unable to boot device in current state: Booted")))(remaining)?;
-
-	warn!(?domain, ?error_code, ?msg, "Parsed xcrun simctl boot error");
-
-	Ok(("", BootOutput::AlreadyBooted))
+/// Parses [BootOutput::AlreadyBooted] and [BootOutput::ErrorLaunchDFailed]
+fn parse_boot_error(input: &str) -> IResult<&str, BootOutput> {
+	map_opt(NSError::nom_from_str, |error| {
+		let output = if error.invalid_state() == Some("Booted") {
+			BootOutput::AlreadyBooted
+		} else if error.any_domain("com.apple.SimLaunchHostService.RequestError") {
+			BootOutput::ErrorLaunchDFailed { error }
+		} else {
+			return None;
+		};
+		warn!(?output, "Parsed xcrun simctl boot error");
+		Some(output)
+	})(input)
 }
 
-/// Parses [BootOutput::ErrorLaunchDFailed]
-/// An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=60):
-/// Unable to boot the Simulator.
-/// launchd failed to respond.
-fn parse_launchd_failed(input: &str) -> IResult<&str, BootOutput> {
-	let (remaining, _preamble) = ws(tag("An error was encountered processing the command"))(input)?;
-	let (remaining, domain) =
-		delimited(tag("(domain="), take_till(|c| c == ','), tag(","))(remaining)?;
-	let (remaining, error_code) = delimited(ws(tag("code=")), digit1, ws(tag("):")))(remaining)?;
-	let (underlying_error, msg) = ws(tag("launchd failed to respond."))(remaining)?;
+#[cfg(test)]
+mod tests {
+	use super::*;
 
-	warn!(
-		?domain,
-		?error_code,
-		?msg,
-		?underlying_error,
-		"Parsed xcrun simctl boot error"
-	);
+	#[test]
+	fn parses_errors() {
+		let already_booted = "An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):\nUnable to boot device in current state: Booted\n";
+		let output = BootOutput::errored_from_str(already_booted);
+		assert!(matches!(output, BootOutput::AlreadyBooted), "{:?}", output);
 
-	Ok((
-		"",
-		BootOutput::ErrorLaunchDFailed {
-			stderr: input.into(),
-		},
-	))
+		for state in ["Shutting Down", "Creating"] {
+			let invalid_state = format!("An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):\nUnable to boot device in current state: {state}\n");
+			let output = BootOutput::errored_from_str(&invalid_state);
+			assert!(
+				matches!(output, BootOutput::ErrorUnImplemented { .. }),
+				"{:?}",
+				output
+			);
+			assert!(output.failed());
+		}
+
+		let launchd_failed = "An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=60):
+Unable to boot the Simulator.
+launchd failed to respond.
+Underlying error (domain=com.apple.SimLaunchHostService.RequestError, code=4):
+	Failed to start launchd_sim: could not bind to session, launchd_sim may have crashed or quit responding
+";
+		let output = BootOutput::errored_from_str(launchd_failed);
+		assert!(
+			matches!(output, BootOutput::ErrorLaunchDFailed { .. }),
+			"{:?}",
+			output
+		);
+	}
 }
@@ -0,0 +1,57 @@
+use crate::prelude::*;
+use crate::shared::identifiers::RuntimeIdentifier;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+
+impl XcRunSimctlInstance<'_> {
+	/// Creates a new simulator.
+	/// `device_type` is an identifier like `com.apple.CoreSimulator.SimDeviceType.iPhone-15-Pro`,
+	/// see [super::list::ListJson::device_types]
+	#[instrument(skip_all, ret, fields(name = name.as_ref(), device_type = device_type.as_ref(), %runtime))]
+	pub fn create(
+		&self,
+		name: impl AsRef<str>,
+		device_type: impl AsRef<str>,
+		runtime: &RuntimeIdentifier,
+	) -> Result<CreateOutput> {
+		CreateOutput::from_bossy_result(
+			self
+				.bossy_command()
+				.with_arg("create")
+				.with_arg(name.as_ref())
+				.with_arg(device_type.as_ref())
+				.with_arg(runtime.as_str())
+				.run_and_wait_for_output(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn create_args() {
+		let xcrun = super::super::echo_xcrun();
+		let runtime: RuntimeIdentifier =
+			serde_json::from_str(r#""com.apple.CoreSimulator.SimRuntime.iOS-17-4""#).unwrap();
+		let output = xcrun
+			.simctl()
+			.create(
+				"Test Phone",
+				"com.apple.CoreSimulator.SimDeviceType.iPhone-15-Pro",
+				&runtime,
+			)
+			.unwrap();
+		match output {
+			CreateOutput::SuccessUnImplemented { stdout } => assert_eq!(
+				stdout,
+				"simctl create Test Phone com.apple.CoreSimulator.SimDeviceType.iPhone-15-Pro com.apple.CoreSimulator.SimRuntime.iOS-17-4\n"
+			),
+			output => panic!("Unexpected output {:?}", output),
+		}
+	}
+}
@@ -0,0 +1,81 @@
+use crate::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum CreateOutput {
+	/// `simctl create` prints the UDID of the new simulator:
+	/// ```text
+	/// 5B9E3A5C-2F4D-4C7B-9B1A-0D3E8F6A7C21
+	/// ```
+	Created { udid: String },
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for CreateOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	/// Parses a UDID, hex digits grouped 8-4-4-4-12
+	fn success_nom_from_str(input: &str) -> IResult<&str, Self> {
+		map_opt(
+			ws(take_till1(|c: char| !(c.is_ascii_hexdigit() || c == '-'))),
+			|udid: &str| {
+				let groups: Vec<usize> = udid.split('-').map(str::len).collect();
+				(groups == [8, 4, 4, 4, 12]).then(|| Self::Created {
+					udid: udid.to_owned(),
+				})
+			},
+		)(input)
+	}
+}
+
+impl PublicCommandOutput for CreateOutput {
+	/// The UDID of the created simulator
+	type PrimarySuccess = String;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			CreateOutput::Created { udid } => Ok(udid),
+			_ => Err(Error::output_errored_with_hint(
+				self,
+				"Check the device type is supported by the runtime, see `xcrun simctl list`",
+			)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_udid() {
+		let output = CreateOutput::success_from_str("5B9E3A5C-2F4D-4C7B-9B1A-0D3E8F6A7C21\n");
+		assert_eq!(
+			output.success().unwrap(),
+			"5B9E3A5C-2F4D-4C7B-9B1A-0D3E8F6A7C21"
+		);
+
+		for garbage in ["", "added\n", "5B9E3A5C-2F4D\n", "Invalid device type\n"] {
+			let output = CreateOutput::success_from_str(garbage);
+			assert!(
+				matches!(output, CreateOutput::SuccessUnImplemented { .. }),
+				"{:?}: {:?}",
+				garbage,
+				output
+			);
+			assert!(output.success().is_err());
+		}
+	}
+}
@@ -0,0 +1,63 @@
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+
+impl XcRunSimctlInstance<'_> {
+	fn delete_command(&self, target: &str) -> bossy::Command {
+		self.bossy_command().with_arg("delete").with_arg(target)
+	}
+
+	/// Deletes a simulator, identified by its UDID or name.
+	/// Prefer the UDID, since names aren't unique
+	#[instrument(skip_all, ret, fields(device = device.as_ref()))]
+	pub fn delete(&self, device: impl AsRef<str>) -> Result<DeleteOutput> {
+		DeleteOutput::from_bossy_result(
+			self
+				.delete_command(device.as_ref())
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Deletes every simulator in the device set
+	#[instrument(skip_all, ret)]
+	pub fn delete_all(&self) -> Result<DeleteOutput> {
+		DeleteOutput::from_bossy_result(self.delete_command("all").run_and_wait_for_output())
+	}
+
+	/// Deletes simulators whose runtime is no longer installed
+	#[instrument(skip_all, ret)]
+	pub fn delete_unavailable(&self) -> Result<DeleteOutput> {
+		DeleteOutput::from_bossy_result(self.delete_command("unavailable").run_and_wait_for_output())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn delete_args() {
+		let xcrun = super::super::echo_xcrun();
+		let simctl = xcrun.simctl();
+		let stdout = |output: DeleteOutput| match output {
+			DeleteOutput::SuccessUnImplemented { stdout } => stdout,
+			output => panic!("Unexpected output {:?}", output),
+		};
+		assert_eq!(
+			stdout(
+				simctl
+					.delete("9A2E3C5B-0000-4000-8000-000000000000")
+					.unwrap()
+			),
+			"simctl delete 9A2E3C5B-0000-4000-8000-000000000000\n"
+		);
+		assert_eq!(stdout(simctl.delete_all().unwrap()), "simctl delete all\n");
+		assert_eq!(
+			stdout(simctl.delete_unavailable().unwrap()),
+			"simctl delete unavailable\n"
+		);
+	}
+}
@@ -0,0 +1,47 @@
+use crate::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum DeleteOutput {
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for DeleteOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+}
+
+impl PublicCommandOutput for DeleteOutput {
+	type PrimarySuccess = ();
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			DeleteOutput::SuccessUnImplemented { .. } => Ok(&()),
+			DeleteOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn delete_output() {
+		assert!(DeleteOutput::success_from_str("").success().is_ok());
+		let output =
+			DeleteOutput::errored_from_str("Invalid device: 9A2E3C5B-0000-4000-8000-000000000000\n");
+		assert!(matches!(output, DeleteOutput::ErrorUnImplemented { .. }));
+		assert!(output.failed());
+	}
+}
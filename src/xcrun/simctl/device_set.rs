@@ -0,0 +1,162 @@
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+/// A device set in a fresh temporary directory,
+/// so that e.g. parallel CI jobs don't share or modify the default simulators.
+///
+/// Use with [XcRunSimctlInstance::with_device_set], and clean up with
+/// [XcRunSimctlInstance::destroy_temp_device_set].
+/// If only dropped, the directory is removed but simulators inside it may be left registered
+/// with CoreSimulator until it next notices
+#[derive(Debug)]
+pub struct TempDeviceSet {
+	path: Option<Utf8PathBuf>,
+}
+
+impl TempDeviceSet {
+	/// Creates a new empty directory under [std::env::temp_dir]
+	pub fn new() -> Result<Self> {
+		let nanos = std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.map(|duration| duration.as_nanos())
+			.unwrap_or_default();
+		let path = Utf8PathBuf::try_from(std::env::temp_dir())?.join(format!(
+			"apple-clis-device-set-{}-{}",
+			std::process::id(),
+			nanos
+		));
+		std::fs::create_dir_all(&path).map_err(|err| Error::FileIo {
+			path: path.clone(),
+			err,
+		})?;
+		debug!(%path, "Created temporary device set");
+		Ok(Self { path: Some(path) })
+	}
+
+	pub fn path(&self) -> &Utf8Path {
+		self
+			.path
+			.as_deref()
+			.expect("TempDeviceSet path is only taken when destroyed")
+	}
+
+	fn remove_dir(path: &Utf8Path) -> Result<()> {
+		std::fs::remove_dir_all(path).map_err(|err| Error::FileIo {
+			path: path.to_owned(),
+			err,
+		})
+	}
+}
+
+impl Drop for TempDeviceSet {
+	fn drop(&mut self) {
+		if let Some(path) = self.path.take() {
+			warn!(%path, "TempDeviceSet dropped without being destroyed, only removing its directory");
+			if let Err(err) = Self::remove_dir(&path) {
+				error!(?err, "Failed to remove temporary device set");
+			}
+		}
+	}
+}
+
+impl<'src> XcRunSimctlInstance<'src> {
+	/// Operates on the device set at `path` instead of the user's default simulators,
+	/// by passing `--set <path>` to every subcommand
+	pub fn with_device_set(mut self, path: impl Into<Utf8PathBuf>) -> Self {
+		self.device_set = Some(path.into());
+		self
+	}
+
+	/// The device set passed to every subcommand, if not the default
+	pub fn device_set(&self) -> Option<&Utf8Path> {
+		self.device_set.as_deref()
+	}
+
+	/// Creates a [TempDeviceSet] and an instance that uses it
+	pub fn with_temp_device_set(&self) -> Result<(XcRunSimctlInstance<'src>, TempDeviceSet)> {
+		let set = TempDeviceSet::new()?;
+		let instance = XcRunSimctlInstance {
+			exec_parent: self.exec_parent,
+			device_set: None,
+		}
+		.with_device_set(set.path());
+		Ok((instance, set))
+	}
+
+	/// Shuts down and deletes every simulator in `set`, then removes its directory.
+	/// If deleting fails, `set` is dropped, which still removes the directory
+	#[instrument(skip_all, fields(path = %set.path()))]
+	pub fn destroy_temp_device_set(&self, mut set: TempDeviceSet) -> Result<()> {
+		let instance = XcRunSimctlInstance {
+			exec_parent: self.exec_parent,
+			device_set: None,
+		}
+		.with_device_set(set.path());
+		// fails harmlessly if nothing is booted, and `delete all` reports real problems
+		let _ = instance.shutdown_all();
+		instance.delete_all()?.success()?;
+		let path = set
+			.path
+			.take()
+			.expect("TempDeviceSet path is only taken when destroyed");
+		TempDeviceSet::remove_dir(&path)?;
+		debug!(%path, "Destroyed temporary device set");
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn temp_device_set_removed_on_drop() {
+		let set = TempDeviceSet::new().unwrap();
+		let path = set.path().to_owned();
+		assert!(path.is_dir());
+		drop(set);
+		assert!(!path.exists());
+	}
+
+	#[test]
+	fn passes_device_set() {
+		let xcrun = super::super::echo_xcrun();
+		let device_name = DeviceName::from_str("iPhone 15 Pro").unwrap();
+		assert_eq!(xcrun.simctl().device_set(), None);
+
+		let (simctl, set) = xcrun.simctl().with_temp_device_set().unwrap();
+		assert_eq!(simctl.device_set(), Some(set.path()));
+		assert_eq!(
+			simctl.pbpaste(&device_name).unwrap().success().unwrap(),
+			&format!("simctl --set {} pbpaste iPhone 15 Pro\n", set.path())
+		);
+		let std_args: Vec<_> = simctl
+			.std_command()
+			.get_args()
+			.map(|arg| arg.to_owned())
+			.collect();
+		assert_eq!(std_args, ["simctl", "--set", set.path().as_str()]);
+
+		// `--set` isn't repeated when changing the device set
+		let simctl = simctl.with_device_set("/tmp/other-set");
+		assert_eq!(
+			simctl.pbpaste(&device_name).unwrap().success().unwrap(),
+			"simctl --set /tmp/other-set pbpaste iPhone 15 Pro\n"
+		);
+
+		let path = set.path().to_owned();
+		xcrun.simctl().destroy_temp_device_set(set).unwrap();
+		assert!(!path.exists());
+	}
+
+	#[test]
+	fn removes_dir_when_destroying_fails() {
+		// Safety: `/bin/false` is a valid executable
+		let xcrun = unsafe { crate::xcrun::XcRunInstance::new_unchecked("/bin/false") };
+		let set = TempDeviceSet::new().unwrap();
+		let path = set.path().to_owned();
+		assert!(xcrun.simctl().destroy_temp_device_set(set).is_err());
+		assert!(!path.exists());
+	}
+}
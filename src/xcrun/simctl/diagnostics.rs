@@ -0,0 +1,439 @@
+//! Collects everything useful for debugging a broken simulator into one archive
+
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+/// What to include in [XcRunSimctlInstance::collect_diagnostics]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticsOptions {
+	/// Includes the logs of this simulator, from [super::list::ListDevice::log_path]
+	pub device: Option<DeviceName>,
+	/// Includes crash reports mentioning this bundle identifier
+	pub bundle_id: Option<String>,
+	/// Includes the output of `simctl diagnose`, which is thorough but slow
+	pub simctl_diagnose: bool,
+}
+
+impl Default for DiagnosticsOptions {
+	fn default() -> Self {
+		Self {
+			device: None,
+			bundle_id: None,
+			simctl_diagnose: true,
+		}
+	}
+}
+
+/// Something that couldn't be collected, which doesn't fail the whole collection
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiagnosticsSkipped {
+	pub item: String,
+	pub reason: String,
+}
+
+/// The result of [XcRunSimctlInstance::collect_diagnostics]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiagnosticsReport {
+	/// The `.tar.gz` archive that was written
+	pub archive: Utf8PathBuf,
+	/// Paths inside the archive
+	pub included: Vec<String>,
+	pub skipped: Vec<DiagnosticsSkipped>,
+}
+
+/// Builds up the staging directory that is archived
+struct Staging {
+	dir: Utf8PathBuf,
+	included: Vec<String>,
+	skipped: Vec<DiagnosticsSkipped>,
+}
+
+impl Staging {
+	fn new() -> Result<Self> {
+		let nanos = std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.map(|duration| duration.as_nanos())
+			.unwrap_or_default();
+		let dir = Utf8PathBuf::try_from(std::env::temp_dir())?.join(format!(
+			"apple-clis-diagnostics-{}-{}",
+			std::process::id(),
+			nanos
+		));
+		create_dir_all(&dir)?;
+		Ok(Self {
+			dir,
+			included: Vec::new(),
+			skipped: Vec::new(),
+		})
+	}
+
+	fn skip(&mut self, item: impl Into<String>, reason: impl std::fmt::Display) {
+		let item = item.into();
+		let reason = reason.to_string();
+		warn!(%item, %reason, "Skipping diagnostics item");
+		self.skipped.push(DiagnosticsSkipped { item, reason });
+	}
+
+	fn write(&mut self, name: &str, contents: impl AsRef<[u8]>) -> Result<()> {
+		let path = self.dir.join(name);
+		std::fs::write(&path, contents).map_err(|err| Error::FileIo { path, err })?;
+		self.included.push(name.to_owned());
+		Ok(())
+	}
+
+	/// Runs `command`, writing its stdout and stderr into `name`.
+	/// If it fails its output is still written, since that is usually the interesting part
+	fn write_command(&mut self, name: &str, mut command: bossy::Command) -> Result<()> {
+		let output = command.run_and_wait_for_output();
+		let (output, err) = match &output {
+			Ok(output) => (output, None),
+			Err(err) => match err.output() {
+				Some(output) => (output, Some(err)),
+				None => {
+					self.skip(name, format!("Couldn't run {}: {}", command.display(), err));
+					return Ok(());
+				}
+			},
+		};
+		let mut contents = output.stdout().to_vec();
+		contents.extend_from_slice(output.stderr());
+		if let Some(err) = err {
+			self.skip(name, format!("{} failed: {}", command.display(), err));
+		}
+		self.write(name, contents)
+	}
+}
+
+impl Staging {
+	fn copy_crash_reports(&mut self, reports: Vec<Utf8PathBuf>) {
+		let dir = self.dir.join("crash-reports");
+		if let Err(err) = create_dir_all(&dir) {
+			self.skip("crash-reports", err);
+			return;
+		}
+		for report in reports {
+			let name = report.file_name().unwrap_or_default();
+			let item = format!("crash-reports/{}", name);
+			match std::fs::copy(&report, dir.join(name)) {
+				Ok(_) => self.included.push(item),
+				Err(err) => self.skip(item, format!("Couldn't copy {}: {}", report, err)),
+			}
+		}
+	}
+}
+
+impl Drop for Staging {
+	fn drop(&mut self) {
+		if let Err(err) = std::fs::remove_dir_all(&self.dir) {
+			warn!(?err, dir = %self.dir, "Failed to remove diagnostics staging directory");
+		}
+	}
+}
+
+fn create_dir_all(path: &Utf8Path) -> Result<()> {
+	std::fs::create_dir_all(path).map_err(|err| Error::FileIo {
+		path: path.to_owned(),
+		err,
+	})
+}
+
+fn copy_dir_all(from: &Utf8Path, to: &Utf8Path) -> Result<()> {
+	create_dir_all(to)?;
+	let entries = from.read_dir_utf8().map_err(|err| Error::FileIo {
+		path: from.to_owned(),
+		err,
+	})?;
+	for entry in entries {
+		let entry = entry.map_err(|err| Error::FileIo {
+			path: from.to_owned(),
+			err,
+		})?;
+		let path = entry.path();
+		let destination = to.join(entry.file_name());
+		if path.is_dir() {
+			copy_dir_all(path, &destination)?;
+		} else {
+			std::fs::copy(path, &destination).map_err(|err| Error::FileIo {
+				path: path.to_owned(),
+				err,
+			})?;
+		}
+	}
+	Ok(())
+}
+
+/// Crash reports in `~/Library/Logs/DiagnosticReports` that mention `bundle_id`
+fn crash_reports(bundle_id: &str) -> Result<Vec<Utf8PathBuf>> {
+	let Some(home) = dirs::home_dir() else {
+		return Ok(Vec::new());
+	};
+	crash_reports_in(
+		&Utf8PathBuf::try_from(home)?.join("Library/Logs/DiagnosticReports"),
+		bundle_id,
+	)
+}
+
+fn crash_reports_in(dir: &Utf8Path, bundle_id: &str) -> Result<Vec<Utf8PathBuf>> {
+	let entries = match dir.read_dir_utf8() {
+		Ok(entries) => entries,
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+		Err(err) => {
+			return Err(Error::FileIo {
+				path: dir.to_owned(),
+				err,
+			})
+		}
+	};
+	let mut reports = Vec::new();
+	for entry in entries.flatten() {
+		let path = entry.path();
+		if !matches!(path.extension(), Some("ips" | "crash")) {
+			continue;
+		}
+		// unreadable reports, e.g. from other users, are ignored
+		if let Ok(contents) = std::fs::read_to_string(path) {
+			if contents.contains(bundle_id) {
+				reports.push(path.to_owned());
+			}
+		}
+	}
+	Ok(reports)
+}
+
+impl XcRunSimctlInstance<'_> {
+	/// Collects `simctl list`, tool versions, and optionally `simctl diagnose`,
+	/// a simulator's logs and crash reports into a `.tar.gz` archive at `archive`.
+	///
+	/// Items that can't be collected are listed in [DiagnosticsReport::skipped]
+	/// rather than failing, since this is usually run when things are already broken.
+	/// Only fails if the archive itself can't be written
+	#[instrument(skip_all, fields(?options, archive = %archive.as_ref()))]
+	pub fn collect_diagnostics(
+		&self,
+		options: &DiagnosticsOptions,
+		archive: impl AsRef<Utf8Path>,
+	) -> Result<DiagnosticsReport> {
+		let archive = archive.as_ref();
+		// `tar -C` changes directory, so make sure the archive isn't written inside the staging directory
+		let archive = match archive.is_absolute() {
+			true => archive.to_owned(),
+			false => {
+				let cwd = std::env::current_dir().map_err(|err| Error::FileIo {
+					path: archive.to_owned(),
+					err,
+				})?;
+				Utf8PathBuf::try_from(cwd)?.join(archive)
+			}
+		};
+		let mut staging = Staging::new()?;
+
+		staging.write(
+			"versions.txt",
+			format!("apple-clis {}\n", env!("CARGO_PKG_VERSION")),
+		)?;
+		staging.write_command(
+			"xcodebuild-version.txt",
+			self
+				.exec_parent
+				.bossy_command()
+				.with_args(["xcodebuild", "-version"]),
+		)?;
+		staging.write_command("sw_vers.txt", bossy::Command::pure("/usr/bin/sw_vers"))?;
+
+		let list = match self.list() {
+			Ok(list) => match list.success() {
+				Ok(list) => {
+					staging.write("simctl-list.json", serde_json::to_vec_pretty(list)?)?;
+					Some(list.clone())
+				}
+				Err(err) => {
+					staging.skip("simctl-list.json", err);
+					None
+				}
+			},
+			Err(err) => {
+				staging.skip("simctl-list.json", err);
+				None
+			}
+		};
+
+		let device = options.device.as_ref().and_then(|name| {
+			let device = list.as_ref().and_then(|list| {
+				list
+					.devices()
+					.filter(|device| &device.name == name)
+					.max_by_key(|device| device.ready())
+					.cloned()
+			});
+			if device.is_none() {
+				staging.skip(
+					"device-logs",
+					format!("No simulator named {:?} was listed", name.to_string()),
+				);
+			}
+			device
+		});
+		if let Some(device) = &device {
+			match device.log_path.exists() {
+				true => match copy_dir_all(&device.log_path, &staging.dir.join("device-logs")) {
+					Ok(()) => staging.included.push("device-logs".into()),
+					Err(err) => staging.skip("device-logs", err),
+				},
+				false => staging.skip(
+					"device-logs",
+					format!(
+						"{} doesn't exist, the simulator may never have booted",
+						device.log_path
+					),
+				),
+			}
+		}
+
+		if options.simctl_diagnose {
+			let mut diagnose = self
+				.bossy_command()
+				.with_args(["diagnose", "-b", "--no-archive"])
+				.with_arg(format!("--output={}", staging.dir.join("simctl-diagnose")));
+			if let Some(device) = &device {
+				diagnose.add_arg(format!("--udid={}", device.udid));
+			}
+			staging.write_command("simctl-diagnose.txt", diagnose)?;
+			if staging.dir.join("simctl-diagnose").exists() {
+				staging.included.push("simctl-diagnose".into());
+			}
+		}
+
+		if let Some(bundle_id) = &options.bundle_id {
+			match crash_reports(bundle_id) {
+				Ok(reports) if reports.is_empty() => staging.skip(
+					"crash-reports",
+					format!("No crash reports mention {}", bundle_id),
+				),
+				Ok(reports) => staging.copy_crash_reports(reports),
+				Err(err) => staging.skip("crash-reports", err),
+			}
+		}
+
+		// without the archive nothing was collected, so this is the one step that fails
+		bossy::Command::pure("/usr/bin/tar")
+			.with_arg("-czf")
+			.with_arg(&archive)
+			.with_arg("-C")
+			.with_arg(&staging.dir)
+			.with_arg(".")
+			.run_and_wait_for_output()?;
+
+		Ok(DiagnosticsReport {
+			archive,
+			included: std::mem::take(&mut staging.included),
+			skipped: std::mem::take(&mut staging.skipped),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sh(script: &str) -> bossy::Command {
+		bossy::Command::pure("/bin/sh").with_args(["-c", script])
+	}
+
+	#[test]
+	fn writes_failed_command_output() {
+		let mut staging = Staging::new().unwrap();
+		staging
+			.write_command("ok.txt", sh("echo out; echo err >&2"))
+			.unwrap();
+		staging
+			.write_command("failed.txt", sh("echo out; echo err >&2; exit 3"))
+			.unwrap();
+		staging
+			.write_command("missing.txt", bossy::Command::pure("/nonexistent/tool"))
+			.unwrap();
+
+		assert_eq!(staging.included, ["ok.txt", "failed.txt"]);
+		assert_eq!(
+			std::fs::read_to_string(staging.dir.join("failed.txt")).unwrap(),
+			"out\nerr\n"
+		);
+		let skipped: Vec<_> = staging
+			.skipped
+			.iter()
+			.map(|skipped| skipped.item.as_str())
+			.collect();
+		assert_eq!(skipped, ["failed.txt", "missing.txt"]);
+		assert!(!staging.dir.join("missing.txt").exists());
+	}
+
+	#[test]
+	fn removes_staging_dir_on_drop() {
+		let staging = Staging::new().unwrap();
+		let dir = staging.dir.clone();
+		assert!(dir.is_dir());
+		drop(staging);
+		assert!(!dir.exists());
+	}
+
+	#[test]
+	fn copies_crash_reports() {
+		let reports = Staging::new().unwrap();
+		std::fs::write(reports.dir.join("App-2024.ips"), "com.example.app crashed").unwrap();
+		std::fs::write(
+			reports.dir.join("Other-2024.ips"),
+			"com.example.other crashed",
+		)
+		.unwrap();
+		std::fs::write(reports.dir.join("App.txt"), "com.example.app").unwrap();
+		let found = crash_reports_in(&reports.dir, "com.example.app").unwrap();
+		assert_eq!(found, [reports.dir.join("App-2024.ips")]);
+
+		let mut staging = Staging::new().unwrap();
+		let mut found = found;
+		found.push(reports.dir.join("Deleted.ips"));
+		staging.copy_crash_reports(found);
+		assert_eq!(staging.included, ["crash-reports/App-2024.ips"]);
+		assert_eq!(staging.skipped[0].item, "crash-reports/Deleted.ips");
+		assert!(staging.dir.join("crash-reports/App-2024.ips").is_file());
+	}
+
+	#[test]
+	fn copies_nested_dirs() {
+		let from = Staging::new().unwrap();
+		create_dir_all(&from.dir.join("nested")).unwrap();
+		std::fs::write(from.dir.join("nested/system.log"), "log").unwrap();
+		let to = Staging::new().unwrap();
+		copy_dir_all(&from.dir, &to.dir.join("copy")).unwrap();
+		assert_eq!(
+			std::fs::read_to_string(to.dir.join("copy/nested/system.log")).unwrap(),
+			"log"
+		);
+		assert!(copy_dir_all(&from.dir.join("missing"), &to.dir.join("missing")).is_err());
+	}
+
+	#[test]
+	fn fails_only_when_archive_cant_be_written() {
+		let xcrun = super::super::echo_xcrun();
+		let options = DiagnosticsOptions {
+			simctl_diagnose: false,
+			..Default::default()
+		};
+		let output = Staging::new().unwrap();
+
+		let archive = output.dir.join("diagnostics.tar.gz");
+		let report = xcrun
+			.simctl()
+			.collect_diagnostics(&options, &archive)
+			.unwrap();
+		assert!(archive.is_file());
+		assert!(report.included.contains(&"versions.txt".to_owned()));
+
+		let missing_dir = output.dir.join("missing/diagnostics.tar.gz");
+		assert!(matches!(
+			xcrun.simctl().collect_diagnostics(&options, &missing_dir),
+			Err(Error::ExecuteErrored(_))
+		));
+		assert!(!missing_dir.exists());
+	}
+}
@@ -0,0 +1,409 @@
+//! Declaratively reconciles the simulators that exist with a [SimulatorMatrix]
+
+use crate::prelude::*;
+use crate::shared::identifiers::RuntimeIdentifier;
+
+use super::list::{ListJson, ListRuntime};
+use super::XcRunSimctlInstance;
+
+/// The simulators a project needs, usually deserialized from TOML:
+/// ```toml
+/// [[simulator]]
+/// device = "iPhone 15 Pro"
+/// runtime = "iOS 17.4"
+///
+/// [[simulator]]
+/// device = "iPad Air 11-inch (M2)"
+/// # runtime defaults to "latest"
+/// name = "CI iPad"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatorMatrix {
+	#[serde(rename = "simulator", default)]
+	pub simulators: Vec<SimulatorSpec>,
+}
+
+impl SimulatorMatrix {
+	pub fn from_toml(toml: &str) -> Result<Self> {
+		Ok(toml::from_str(toml)?)
+	}
+
+	pub fn from_toml_file(path: impl AsRef<Utf8Path>) -> Result<Self> {
+		let path = path.as_ref();
+		let toml = std::fs::read_to_string(path).map_err(|err| Error::FileIo {
+			path: path.to_owned(),
+			err,
+		})?;
+		Self::from_toml(&toml)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatorSpec {
+	/// Matched against the name of a device type, e.g. "iPhone 15 Pro"
+	pub device: DeviceName,
+	#[serde(default)]
+	pub runtime: RuntimeSpec,
+	/// The name of the simulator, defaulting to [SimulatorSpec::device]
+	pub name: Option<String>,
+}
+
+impl SimulatorSpec {
+	pub fn simulator_name(&self) -> String {
+		self.name.clone().unwrap_or_else(|| self.device.to_string())
+	}
+}
+
+/// Which runtime a [SimulatorSpec] should be created with
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum RuntimeSpec {
+	/// The newest available iOS runtime, written as "latest"
+	#[default]
+	Latest,
+	/// A runtime name like "iOS 17.4", or an identifier like
+	/// "com.apple.CoreSimulator.SimRuntime.iOS-17-4"
+	Named(String),
+}
+
+impl From<String> for RuntimeSpec {
+	fn from(value: String) -> Self {
+		match value.eq_ignore_ascii_case("latest") {
+			true => RuntimeSpec::Latest,
+			false => RuntimeSpec::Named(value),
+		}
+	}
+}
+
+impl From<RuntimeSpec> for String {
+	fn from(spec: RuntimeSpec) -> Self {
+		match spec {
+			RuntimeSpec::Latest => "latest".into(),
+			RuntimeSpec::Named(name) => name,
+		}
+	}
+}
+
+impl RuntimeSpec {
+	/// Finds the matching runtime, even if it is unavailable
+	fn resolve<'list>(&self, runtimes: &'list [ListRuntime]) -> Option<&'list ListRuntime> {
+		match self {
+			RuntimeSpec::Latest => runtimes
+				.iter()
+				.filter(|runtime| runtime.is_available)
+				.filter(|runtime| {
+					runtime.platform.as_deref() == Some("iOS") || runtime.name.starts_with("iOS")
+				})
+				.max_by_key(|runtime| runtime.version_components()),
+			RuntimeSpec::Named(name) => runtimes.iter().find(|runtime| {
+				runtime.name.eq_ignore_ascii_case(name) || runtime.identifier.as_str() == name
+			}),
+		}
+	}
+}
+
+/// A simulator that matches a [SimulatorSpec]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EnsureExisting {
+	pub name: String,
+	pub udid: String,
+	pub runtime: RuntimeIdentifier,
+}
+
+/// A simulator that needs to be created
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EnsureCreate {
+	pub name: String,
+	/// E.g. "com.apple.CoreSimulator.SimDeviceType.iPhone-15-Pro"
+	pub device_type: String,
+	pub runtime: RuntimeIdentifier,
+}
+
+/// A [SimulatorSpec] that can't be satisfied
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EnsureUnavailable {
+	pub spec: SimulatorSpec,
+	pub reason: String,
+}
+
+/// What [XcRunSimctlInstance::ensure] will do, computed by [EnsurePlan::new]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EnsurePlan {
+	pub existing: Vec<EnsureExisting>,
+	pub create: Vec<EnsureCreate>,
+	pub unavailable: Vec<EnsureUnavailable>,
+	/// Simulators not matching any spec, deleted only if requested.
+	/// Simulators matching an unavailable spec, or duplicates of an existing one, are never unlisted
+	pub unlisted: Vec<EnsureExisting>,
+}
+
+impl EnsurePlan {
+	pub fn new(matrix: &SimulatorMatrix, list: &ListJson) -> Self {
+		let mut plan = EnsurePlan::default();
+		// UDIDs of every simulator matching a spec, as far as it could be resolved
+		let mut listed: Vec<&str> = Vec::new();
+		for spec in &matrix.simulators {
+			let name = spec.simulator_name();
+			let device = spec.device.to_string();
+			let runtime = spec.runtime.resolve(list.runtimes());
+			let device_type = list
+				.device_types()
+				.iter()
+				.find(|device_type| device_type.name == device);
+			listed.extend(
+				list
+					.devices_with_runtime()
+					.filter(|(runtime_id, listed)| {
+						listed.name.to_string() == name
+							&& runtime.map_or(true, |runtime| **runtime_id == runtime.identifier)
+							&& device_type.map_or(true, |device_type| {
+								listed.device_type_identifier == device_type.identifier
+							})
+					})
+					.map(|(_, listed)| listed.udid.as_str()),
+			);
+
+			let Some(runtime) = runtime else {
+				plan.unavailable.push(EnsureUnavailable {
+					spec: spec.clone(),
+					reason: match &spec.runtime {
+						RuntimeSpec::Latest => "No iOS runtime is installed".into(),
+						RuntimeSpec::Named(name) => format!("Runtime {:?} is not installed", name),
+					},
+				});
+				continue;
+			};
+			if !runtime.is_available {
+				plan.unavailable.push(EnsureUnavailable {
+					spec: spec.clone(),
+					reason: format!("Runtime {} is installed but unavailable", runtime.name),
+				});
+				continue;
+			}
+			let Some(device_type) = device_type else {
+				plan.unavailable.push(EnsureUnavailable {
+					spec: spec.clone(),
+					reason: format!("No device type named {:?}", device),
+				});
+				continue;
+			};
+
+			let existing = list.devices_with_runtime().find(|(runtime_id, listed)| {
+				**runtime_id == runtime.identifier
+					&& listed.is_available
+					&& listed.name.to_string() == name
+					&& listed.device_type_identifier == device_type.identifier
+			});
+			match existing {
+				Some((_, listed)) => plan.existing.push(EnsureExisting {
+					name,
+					udid: listed.udid.clone(),
+					runtime: runtime.identifier.clone(),
+				}),
+				None => {
+					let create = EnsureCreate {
+						name,
+						device_type: device_type.identifier.clone(),
+						runtime: runtime.identifier.clone(),
+					};
+					// the same spec listed twice only needs creating once
+					if !plan.create.contains(&create) {
+						plan.create.push(create);
+					}
+				}
+			}
+		}
+
+		plan.unlisted = list
+			.devices_with_runtime()
+			.filter(|(_, device)| !listed.contains(&device.udid.as_str()))
+			.map(|(runtime, listed)| EnsureExisting {
+				name: listed.name.to_string(),
+				udid: listed.udid.clone(),
+				runtime: runtime.clone(),
+			})
+			.collect();
+		plan
+	}
+}
+
+/// The result of [XcRunSimctlInstance::ensure]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EnsureReport {
+	pub plan: EnsurePlan,
+	/// The UDIDs of created simulators, in the order of [EnsurePlan::create]
+	pub created: Vec<String>,
+	/// The UDIDs of deleted unlisted simulators
+	pub deleted: Vec<String>,
+}
+
+impl XcRunSimctlInstance<'_> {
+	/// Creates the simulators in `matrix` that don't exist yet,
+	/// and deletes simulators not in `matrix` if `delete_unlisted`.
+	/// Specs whose runtime or device type isn't installed are reported in
+	/// [EnsurePlan::unavailable] rather than failing.
+	///
+	/// With `dry_run`, only computes the [EnsurePlan]
+	#[instrument(skip_all, fields(simulators = matrix.simulators.len(), delete_unlisted, dry_run))]
+	pub fn ensure(
+		&self,
+		matrix: &SimulatorMatrix,
+		delete_unlisted: bool,
+		dry_run: bool,
+	) -> Result<EnsureReport> {
+		let list = self.list()?;
+		let plan = EnsurePlan::new(matrix, list.success()?);
+		let mut report = EnsureReport {
+			plan,
+			created: Vec::new(),
+			deleted: Vec::new(),
+		};
+		if dry_run {
+			return Ok(report);
+		}
+		for create in &report.plan.create {
+			let output = self.create(&create.name, &create.device_type, &create.runtime)?;
+			let udid = output.success()?;
+			info!(name = %create.name, %udid, "Created simulator");
+			report.created.push(udid.clone());
+		}
+		if delete_unlisted {
+			for unlisted in &report.plan.unlisted {
+				self.delete(&unlisted.udid)?.success()?;
+				info!(name = %unlisted.name, udid = %unlisted.udid, "Deleted unlisted simulator");
+				report.deleted.push(unlisted.udid.clone());
+			}
+		}
+		Ok(report)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn list() -> ListJson {
+		serde_json::from_str(include_str!(concat!(
+			env!("CARGO_MANIFEST_DIR"),
+			"/tests/simctl-list-full.json"
+		)))
+		.unwrap()
+	}
+
+	#[test]
+	fn plans_matrix() {
+		let matrix = SimulatorMatrix::from_toml(
+			r#"
+			[[simulator]]
+			device = "iPhone 15 Pro"
+			runtime = "iOS 17.4"
+
+			[[simulator]]
+			device = "iPad Air 11-inch (M2)"
+			name = "CI iPad"
+
+			[[simulator]]
+			device = "iPhone 15"
+			runtime = "iOS 16.4"
+
+			[[simulator]]
+			device = "iPhone 15"
+			runtime = "iOS 99.0"
+			"#,
+		)
+		.unwrap();
+		let list = list();
+		let plan = EnsurePlan::new(&matrix, &list);
+
+		assert_eq!(plan.existing.len(), 1);
+		assert_eq!(plan.existing[0].name, "iPhone 15 Pro");
+		assert_eq!(
+			plan.existing[0].runtime.as_str(),
+			"com.apple.CoreSimulator.SimRuntime.iOS-17-4"
+		);
+
+		// "latest" resolves to iOS 17.5, and the custom name doesn't exist yet
+		assert_eq!(
+			plan.create,
+			[EnsureCreate {
+				name: "CI iPad".into(),
+				device_type: "com.apple.CoreSimulator.SimDeviceType.iPad-Air-11-inch-M2".into(),
+				runtime: serde_json::from_str("\"com.apple.CoreSimulator.SimRuntime.iOS-17-5\"").unwrap(),
+			}]
+		);
+
+		// iOS 16.4 isn't in `runtimes`, nor is iOS 99.0
+		assert_eq!(plan.unavailable.len(), 2);
+
+		// the existing "iPhone 15 Pro", and both "iPhone 15"s since their spec's runtime isn't installed
+		assert_eq!(plan.unlisted.len(), list.devices().count() - 3);
+	}
+
+	#[test]
+	fn keeps_unavailable_and_duplicate_simulators() {
+		let mut json: serde_json::Value = serde_json::from_str(include_str!(concat!(
+			env!("CARGO_MANIFEST_DIR"),
+			"/tests/simctl-list-full.json"
+		)))
+		.unwrap();
+		// iOS 17.5 is installed but unavailable
+		for runtime in json["runtimes"].as_array_mut().unwrap() {
+			if runtime["name"] == "iOS 17.5" {
+				runtime["isAvailable"] = false.into();
+			}
+		}
+		// a second "iPhone 15 Pro" on iOS 17.4
+		let devices = json["devices"]["com.apple.CoreSimulator.SimRuntime.iOS-17-4"]
+			.as_array_mut()
+			.unwrap();
+		let mut duplicate = devices
+			.iter()
+			.find(|device| device["name"] == "iPhone 15 Pro")
+			.unwrap()
+			.clone();
+		duplicate["udid"] = "00000000-0000-0000-0000-000000000000".into();
+		devices.push(duplicate);
+		let list: ListJson = serde_json::from_value(json).unwrap();
+
+		let matrix = SimulatorMatrix::from_toml(
+			r#"
+			[[simulator]]
+			device = "iPhone 15 Pro"
+			runtime = "iOS 17.4"
+
+			[[simulator]]
+			device = "iPhone 15"
+			runtime = "iOS 17.5"
+
+			[[simulator]]
+			device = "iPhone 14"
+			runtime = "iOS 16.4"
+			"#,
+		)
+		.unwrap();
+		let plan = EnsurePlan::new(&matrix, &list);
+
+		assert_eq!(plan.existing.len(), 1);
+		assert_eq!(plan.unavailable.len(), 2);
+		let unlisted = |name: &str| {
+			plan
+				.unlisted
+				.iter()
+				.filter(|unlisted| unlisted.name == name)
+				.map(|unlisted| unlisted.runtime.as_str())
+				.collect::<Vec<_>>()
+		};
+		// neither the duplicate "iPhone 15 Pro" nor the one on iOS 17.5
+		assert_eq!(
+			unlisted("iPhone 15 Pro"),
+			["com.apple.CoreSimulator.SimRuntime.iOS-17-5"]
+		);
+		// only the unavailable iOS 17.5 runtime's "iPhone 15" is kept
+		assert_eq!(
+			unlisted("iPhone 15"),
+			["com.apple.CoreSimulator.SimRuntime.iOS-17-4"]
+		);
+		assert!(unlisted("iPhone 14").is_empty());
+		assert_eq!(plan.unlisted.len(), list.devices().count() - 4);
+	}
+}
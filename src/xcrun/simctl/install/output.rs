@@ -7,6 +7,31 @@ pub enum InstallOutput {
 	#[doc = include_doc!(cmd_success)]
 	SuccessUnImplemented { stdout: String },
 
+	/// The .app was built for a different platform, e.g. a real device
+	/// instead of the simulator:
+	/// ```text
+	/// An error was encountered processing the command (domain=IXUserPresentableErrorDomain, code=4):
+	/// Unable to Install “Example”
+	/// Failed to find matching arch for 64-bit Mach-O input file /path/to/Example.app/Example
+	/// ```
+	ErrorWrongPlatform { error: NSError },
+
+	/// The .app's `Info.plist` doesn't contain a `CFBundleIdentifier`:
+	/// ```text
+	/// An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=22):
+	/// Failed to install the requested application
+	/// The application's Info.plist does not contain CFBundleIdentifier.
+	/// ```
+	ErrorMissingBundleIdentifier { error: NSError },
+
+	/// The .app's executable is missing or not a valid executable:
+	/// ```text
+	/// An error was encountered processing the command (domain=IXUserPresentableErrorDomain, code=4):
+	/// Unable to Install “Example”
+	/// The executable is missing or invalid.
+	/// ```
+	ErrorInvalidExecutable { error: NSError },
+
 	#[doc = include_doc!(cmd_error)]
 	ErrorUnImplemented { stderr: String },
 }
@@ -19,6 +44,10 @@ impl CommandNomParsable for InstallOutput {
 	fn error_unimplemented(stderr: String) -> Self {
 		InstallOutput::ErrorUnImplemented { stderr }
 	}
+
+	fn errored_nom_from_str(input: &str) -> IResult<&str, Self> {
+		parse_install_error(input)
+	}
 }
 
 impl PublicCommandOutput for InstallOutput {
@@ -27,7 +56,111 @@ impl PublicCommandOutput for InstallOutput {
 	fn success(&self) -> Result<&Self::PrimarySuccess> {
 		match self {
 			InstallOutput::SuccessUnImplemented { .. } => Ok(&()),
+			InstallOutput::ErrorWrongPlatform { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"The .app was not built for the simulator. Try building for a simulator target, e.g. `aarch64-apple-ios-sim`",
+			)),
+			InstallOutput::ErrorMissingBundleIdentifier { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Add a `CFBundleIdentifier` key to the .app's Info.plist",
+			)),
+			InstallOutput::ErrorInvalidExecutable { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Check that the .app contains an executable matching the `CFBundleExecutable` key in its Info.plist",
+			)),
 			InstallOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
 		}
 	}
 }
+
+/// Parses the known variants of [InstallOutput] errors
+fn parse_install_error(input: &str) -> IResult<&str, InstallOutput> {
+	map_opt(NSError::nom_from_str, |error| {
+		// IXUserPresentableErrorDomain code 4 is the generic "Unable to Install",
+		// the message distinguishes the reason
+		let unable_to_install = error.any_is("IXUserPresentableErrorDomain", 4);
+		let output = if unable_to_install
+			&& (error.any_message_contains("Failed to find matching arch")
+				|| error.any_message_contains("built for a different platform"))
+		{
+			InstallOutput::ErrorWrongPlatform { error }
+		} else if error.any_is("NSPOSIXErrorDomain", 22)
+			&& error.any_message_contains("CFBundleIdentifier")
+		{
+			InstallOutput::ErrorMissingBundleIdentifier { error }
+		} else if unable_to_install && error.any_message_contains("executable") {
+			InstallOutput::ErrorInvalidExecutable { error }
+		} else {
+			return None;
+		};
+		warn!(?output, "Parsed xcrun simctl install error");
+		Some(output)
+	})(input)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_errors() {
+		let wrong_platform = "An error was encountered processing the command (domain=IXUserPresentableErrorDomain, code=4):
+Unable to Install “Example”
+Please try again later.
+Failed to find matching arch for 64-bit Mach-O input file /path/to/Example.app/Example
+";
+		let output = InstallOutput::errored_from_str(wrong_platform);
+		assert!(
+			matches!(output, InstallOutput::ErrorWrongPlatform { .. }),
+			"{:?}",
+			output
+		);
+
+		let missing_id =
+			"An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=22):
+Failed to install the requested application
+The application's Info.plist does not contain CFBundleIdentifier.
+Ensure your bundle contains a CFBundleIdentifier.
+";
+		let output = InstallOutput::errored_from_str(missing_id);
+		assert!(
+			matches!(output, InstallOutput::ErrorMissingBundleIdentifier { .. }),
+			"{:?}",
+			output
+		);
+
+		let invalid_executable = "An error was encountered processing the command (domain=IXUserPresentableErrorDomain, code=4):
+Unable to Install “Example”
+The executable is missing or invalid.
+";
+		let output = InstallOutput::errored_from_str(invalid_executable);
+		assert!(
+			matches!(output, InstallOutput::ErrorInvalidExecutable { .. }),
+			"{:?}",
+			output
+		);
+
+		// matching message, but not the domain and code
+		let other_domain =
+			"An error was encountered processing the command (domain=MIInstallerErrorDomain, code=13):
+The application's Info.plist does not contain CFBundleIdentifier.
+";
+		let output = InstallOutput::errored_from_str(other_domain);
+		assert!(
+			matches!(output, InstallOutput::ErrorUnImplemented { .. }),
+			"{:?}",
+			output
+		);
+
+		let unknown =
+			"An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=2):
+No such file or directory
+";
+		let output = InstallOutput::errored_from_str(unknown);
+		assert!(
+			matches!(output, InstallOutput::ErrorUnImplemented { .. }),
+			"{:?}",
+			output
+		);
+	}
+}
@@ -0,0 +1,373 @@
+use std::{
+	io::Read,
+	process::{Child, ExitStatus, Stdio},
+	thread::JoinHandle,
+};
+
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ScreenshotType {
+	Png,
+	Tiff,
+	Bmp,
+	Gif,
+	Jpeg,
+	Heic,
+}
+
+impl ScreenshotType {
+	fn as_arg(&self) -> &'static str {
+		match self {
+			ScreenshotType::Png => "png",
+			ScreenshotType::Tiff => "tiff",
+			ScreenshotType::Bmp => "bmp",
+			ScreenshotType::Gif => "gif",
+			ScreenshotType::Jpeg => "jpeg",
+			ScreenshotType::Heic => "heic",
+		}
+	}
+}
+
+/// Which display of the simulator to capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum IoDisplay {
+	Internal,
+	External,
+}
+
+impl IoDisplay {
+	fn as_arg(&self) -> &'static str {
+		match self {
+			IoDisplay::Internal => "internal",
+			IoDisplay::External => "external",
+		}
+	}
+}
+
+/// How to treat the non-rectangular parts of the display, e.g. rounded corners and the notch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum IoMask {
+	Ignored,
+	Alpha,
+	Black,
+}
+
+impl IoMask {
+	fn as_arg(&self) -> &'static str {
+		match self {
+			IoMask::Ignored => "ignored",
+			IoMask::Alpha => "alpha",
+			IoMask::Black => "black",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum VideoCodec {
+	H264,
+	Hevc,
+}
+
+impl VideoCodec {
+	fn as_arg(&self) -> &'static str {
+		match self {
+			VideoCodec::H264 => "h264",
+			VideoCodec::Hevc => "hevc",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::Args))]
+pub struct ScreenshotOptions {
+	/// Defaults to the type implied by the file extension, or png
+	#[cfg_attr(feature = "cli", arg(long = "type", value_enum))]
+	pub image_type: Option<ScreenshotType>,
+
+	#[cfg_attr(feature = "cli", arg(long, value_enum))]
+	pub display: Option<IoDisplay>,
+
+	#[cfg_attr(feature = "cli", arg(long, value_enum))]
+	pub mask: Option<IoMask>,
+}
+
+impl ScreenshotOptions {
+	fn args(&self) -> Vec<String> {
+		let mut args = Vec::new();
+		if let Some(image_type) = self.image_type {
+			args.push(format!("--type={}", image_type.as_arg()));
+		}
+		if let Some(display) = self.display {
+			args.push(format!("--display={}", display.as_arg()));
+		}
+		if let Some(mask) = self.mask {
+			args.push(format!("--mask={}", mask.as_arg()));
+		}
+		args
+	}
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::Args))]
+pub struct RecordVideoOptions {
+	/// Defaults to hevc
+	#[cfg_attr(feature = "cli", arg(long, value_enum))]
+	pub codec: Option<VideoCodec>,
+
+	#[cfg_attr(feature = "cli", arg(long, value_enum))]
+	pub display: Option<IoDisplay>,
+
+	#[cfg_attr(feature = "cli", arg(long, value_enum))]
+	pub mask: Option<IoMask>,
+
+	/// Overwrites the output file if it already exists
+	#[cfg_attr(feature = "cli", arg(long))]
+	pub force: bool,
+}
+
+impl RecordVideoOptions {
+	fn args(&self) -> Vec<String> {
+		let mut args = Vec::new();
+		if let Some(codec) = self.codec {
+			args.push(format!("--codec={}", codec.as_arg()));
+		}
+		if let Some(display) = self.display {
+			args.push(format!("--display={}", display.as_arg()));
+		}
+		if let Some(mask) = self.mask {
+			args.push(format!("--mask={}", mask.as_arg()));
+		}
+		if self.force {
+			args.push("--force".into());
+		}
+		args
+	}
+}
+
+/// A running `simctl io recordVideo` process.
+///
+/// Call [VideoRecording::stop] to finish the recording,
+/// which sends `SIGINT` so that `simctl` finalizes the video file.
+/// If dropped without stopping, the recording is stopped the same way
+/// but any error is only logged.
+#[derive(Debug)]
+pub struct VideoRecording {
+	child: Option<Child>,
+	path: Utf8PathBuf,
+	/// stdout and stderr are read on their own threads while recording,
+	/// so a full pipe can't block `simctl`
+	stdout: Option<JoinHandle<std::io::Result<String>>>,
+	stderr: Option<JoinHandle<std::io::Result<String>>>,
+}
+
+fn read_on_thread(
+	pipe: Option<impl Read + Send + 'static>,
+) -> Option<JoinHandle<std::io::Result<String>>> {
+	pipe.map(|mut pipe| {
+		std::thread::spawn(move || {
+			let mut output = String::new();
+			pipe.read_to_string(&mut output).map(|_| output)
+		})
+	})
+}
+
+fn join_output(handle: Option<JoinHandle<std::io::Result<String>>>) -> Result<String> {
+	match handle.map(JoinHandle::join) {
+		Some(Ok(output)) => output.map_err(Error::ChildProcessIo),
+		Some(Err(_)) => {
+			warn!("Thread reading video recording output panicked");
+			Ok(String::new())
+		}
+		None => Ok(String::new()),
+	}
+}
+
+impl VideoRecording {
+	fn new(mut child: Child, path: Utf8PathBuf) -> Self {
+		let stdout = read_on_thread(child.stdout.take());
+		let stderr = read_on_thread(child.stderr.take());
+		Self {
+			child: Some(child),
+			path,
+			stdout,
+			stderr,
+		}
+	}
+
+	/// Where the video is being recorded to
+	pub fn path(&self) -> &Utf8Path {
+		&self.path
+	}
+
+	/// Sends `SIGINT` if `simctl` is still recording, then waits for it to exit.
+	/// `simctl` may have already exited, e.g. because the file exists without `--force`
+	fn interrupt(child: &mut Child) -> Result<ExitStatus> {
+		if let Some(status) = child.try_wait().map_err(Error::ChildProcessIo)? {
+			debug!(?status, "simctl recordVideo had already exited");
+			return Ok(status);
+		}
+		let killed = bossy::Command::pure("/bin/kill")
+			.with_arg("-INT")
+			.with_arg(child.id().to_string())
+			.run_and_wait();
+		if let Err(err) = killed {
+			// it may have exited since checking
+			if child.try_wait().map_err(Error::ChildProcessIo)?.is_none() {
+				Err(err)?
+			}
+		}
+		child.wait().map_err(Error::ChildProcessIo)
+	}
+
+	/// Stops the recording, waiting for `simctl` to finish writing the video file
+	#[instrument(skip_all, ret, fields(path = %self.path))]
+	pub fn stop(mut self) -> Result<RecordVideoOutput> {
+		let Some(mut child) = self.child.take() else {
+			unreachable!("VideoRecording child is only taken when stopping or dropping")
+		};
+		let status = Self::interrupt(&mut child)?;
+		let stdout = join_output(self.stdout.take())?;
+		let stderr = join_output(self.stderr.take())?;
+		trace!(%stdout, "simctl recordVideo exited");
+		Ok(match status.success() {
+			true => RecordVideoOutput::Recorded {
+				path: self.path.clone(),
+			},
+			false => RecordVideoOutput::errored_from_str(&stderr),
+		})
+	}
+}
+
+impl Drop for VideoRecording {
+	fn drop(&mut self) {
+		if let Some(mut child) = self.child.take() {
+			warn!(path = %self.path, "VideoRecording dropped without calling stop, stopping now");
+			if let Err(err) = Self::interrupt(&mut child) {
+				error!(?err, "Failed to stop video recording");
+			}
+			let _ = join_output(self.stdout.take());
+			let _ = join_output(self.stderr.take());
+		}
+	}
+}
+
+impl XcRunSimctlInstance<'_> {
+	fn io_args(device_name: &DeviceName) -> [String; 2] {
+		[device_name.to_string(), "io".into()]
+	}
+
+	/// Takes a screenshot of a booted simulator and saves it to `path`
+	#[instrument(skip_all, ret, fields(%device_name, path = %path.as_ref()))]
+	pub fn screenshot(
+		&self,
+		device_name: &DeviceName,
+		path: impl AsRef<Utf8Path>,
+		options: &ScreenshotOptions,
+	) -> Result<ScreenshotOutput> {
+		let path = path.as_ref();
+		let output = ScreenshotOutput::from_bossy_result(
+			self
+				.bossy_command()
+				.with_args(Self::io_args(device_name))
+				.with_arg("screenshot")
+				.with_args(options.args())
+				.with_arg(path)
+				.run_and_wait_for_output(),
+		)?;
+		Ok(match output {
+			ScreenshotOutput::SuccessUnImplemented { .. } => ScreenshotOutput::Saved {
+				path: path.to_owned(),
+			},
+			output => output,
+		})
+	}
+
+	/// Starts recording a video of a booted simulator to `path`.
+	/// The recording continues until [VideoRecording::stop] is called.
+	#[instrument(skip_all, fields(%device_name, path = %path.as_ref()))]
+	pub fn record_video(
+		&self,
+		device_name: &DeviceName,
+		path: impl AsRef<Utf8Path>,
+		options: &RecordVideoOptions,
+	) -> Result<VideoRecording> {
+		let path = path.as_ref();
+		let child = self
+			.std_command()
+			.args(Self::io_args(device_name))
+			.arg("recordVideo")
+			.args(options.args())
+			.arg(path)
+			.stdin(Stdio::null())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::piped())
+			.spawn()
+			.map_err(Error::ChildProcessIo)?;
+		debug!(pid = child.id(), "Started recording video");
+		Ok(VideoRecording::new(child, path.to_owned()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn options_args() {
+		let options = ScreenshotOptions {
+			image_type: Some(ScreenshotType::Jpeg),
+			display: None,
+			mask: Some(IoMask::Black),
+		};
+		assert_eq!(options.args(), ["--type=jpeg", "--mask=black"]);
+
+		let options = RecordVideoOptions {
+			codec: Some(VideoCodec::H264),
+			force: true,
+			..Default::default()
+		};
+		assert_eq!(options.args(), ["--codec=h264", "--force"]);
+	}
+
+	fn fake_recording(script: &str) -> VideoRecording {
+		let child = std::process::Command::new("/bin/sh")
+			.args(["-c", script])
+			.stdin(Stdio::null())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::piped())
+			.spawn()
+			.unwrap();
+		VideoRecording::new(child, "video.mp4".into())
+	}
+
+	#[test]
+	fn stops_recordings() {
+		let recording =
+			fake_recording("trap 'exit 0' INT; echo Recording started; while :; do sleep 0.05; done");
+		std::thread::sleep(std::time::Duration::from_millis(200));
+		let output = recording.stop().unwrap();
+		assert_eq!(output.success().unwrap(), "video.mp4");
+
+		// e.g. the file already exists without --force
+		let recording = fake_recording("echo 'Error: File exists' >&2; exit 1");
+		std::thread::sleep(std::time::Duration::from_millis(200));
+		let output = recording.stop().unwrap();
+		match &output {
+			RecordVideoOutput::ErrorFileExists { stderr } => {
+				assert_eq!(stderr, "Error: File exists\n")
+			}
+			output => panic!("Expected an error, got {:?}", output),
+		}
+		assert!(output.failed());
+	}
+}
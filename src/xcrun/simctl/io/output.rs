@@ -0,0 +1,124 @@
+use crate::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum ScreenshotOutput {
+	/// The screenshot was written to `path`
+	Saved { path: Utf8PathBuf },
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for ScreenshotOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+}
+
+impl PublicCommandOutput for ScreenshotOutput {
+	type PrimarySuccess = Utf8PathBuf;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			ScreenshotOutput::Saved { path } => Ok(path),
+			_ => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum RecordVideoOutput {
+	/// The recording was stopped and the video finalized at `path`
+	Recorded { path: Utf8PathBuf },
+
+	/// The output file already exists and `--force` wasn't passed, e.g.
+	/// `NSCocoaErrorDomain` code 516 or "File exists"
+	ErrorFileExists { stderr: String },
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for RecordVideoOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn errored_from_str(input: &str) -> Self {
+		let file_exists = match NSError::nom_from_str(input) {
+			Ok((_, error)) => {
+				error.any_is("NSCocoaErrorDomain", 516) || error.any_is("NSPOSIXErrorDomain", 17)
+			}
+			Err(_) => input.contains("File exists"),
+		};
+		match file_exists {
+			true => Self::ErrorFileExists {
+				stderr: input.to_owned(),
+			},
+			false => Self::error_unimplemented(input.to_owned()),
+		}
+	}
+}
+
+impl PublicCommandOutput for RecordVideoOutput {
+	type PrimarySuccess = Utf8PathBuf;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			RecordVideoOutput::Recorded { path } => Ok(path),
+			RecordVideoOutput::ErrorFileExists { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Pass --force to overwrite the existing file",
+			)),
+			_ => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn hint(output: &RecordVideoOutput) -> Option<String> {
+		match output.success() {
+			Err(Error::OutputErrored { help_hint, .. }) => help_hint,
+			result => panic!("Expected an error, got {:?}", result),
+		}
+	}
+
+	#[test]
+	fn record_video_errors() {
+		let exists = RecordVideoOutput::errored_from_str(
+			"An error was encountered processing the command (domain=NSCocoaErrorDomain, code=516):\nThe file “video.mp4” couldn’t be saved because a file with the same name already exists.\n",
+		);
+		assert!(matches!(exists, RecordVideoOutput::ErrorFileExists { .. }));
+		assert!(hint(&exists).unwrap().contains("--force"));
+
+		let not_booted = RecordVideoOutput::errored_from_str(
+			"An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):\nUnable to lookup in current state: Shutdown\n",
+		);
+		assert!(matches!(
+			not_booted,
+			RecordVideoOutput::ErrorUnImplemented { .. }
+		));
+		assert_eq!(hint(&not_booted), None);
+	}
+}
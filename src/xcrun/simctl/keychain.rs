@@ -0,0 +1,89 @@
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+
+impl XcRunSimctlInstance<'_> {
+	fn keychain_command(&self, device_name: &DeviceName, action: &str) -> bossy::Command {
+		self
+			.bossy_command()
+			.with_arg("keychain")
+			.with_arg(device_name.to_string())
+			.with_arg(action)
+	}
+
+	/// Adds a certificate to the simulator's trusted root store,
+	/// e.g. the CA of a local HTTPS server
+	#[instrument(skip_all, ret, fields(%device_name, cert = %cert.as_ref()))]
+	pub fn keychain_add_root_cert(
+		&self,
+		device_name: &DeviceName,
+		cert: impl AsRef<Utf8Path>,
+	) -> Result<KeychainOutput> {
+		KeychainOutput::from_bossy_result(
+			self
+				.keychain_command(device_name, "add-root-cert")
+				.with_arg(cert.as_ref())
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Adds a certificate to the simulator's keychain, without trusting it as a root
+	#[instrument(skip_all, ret, fields(%device_name, cert = %cert.as_ref()))]
+	pub fn keychain_add_cert(
+		&self,
+		device_name: &DeviceName,
+		cert: impl AsRef<Utf8Path>,
+	) -> Result<KeychainOutput> {
+		KeychainOutput::from_bossy_result(
+			self
+				.keychain_command(device_name, "add-cert")
+				.with_arg(cert.as_ref())
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Resets the simulator's keychain, removing any added certificates
+	#[instrument(skip_all, ret, fields(%device_name))]
+	pub fn keychain_reset(&self, device_name: &DeviceName) -> Result<KeychainOutput> {
+		KeychainOutput::from_bossy_result(
+			self
+				.keychain_command(device_name, "reset")
+				.run_and_wait_for_output(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn keychain_args() {
+		let xcrun = super::super::echo_xcrun();
+		let simctl = xcrun.simctl();
+		let device_name = DeviceName::from_str("iPhone 15 Pro").unwrap();
+		let stdout = |output: KeychainOutput| match output {
+			KeychainOutput::SuccessUnImplemented { stdout } => stdout,
+			output => panic!("Unexpected output {:?}", output),
+		};
+		assert_eq!(
+			stdout(
+				simctl
+					.keychain_add_root_cert(&device_name, "ca.pem")
+					.unwrap()
+			),
+			"simctl keychain iPhone 15 Pro add-root-cert ca.pem\n"
+		);
+		assert_eq!(
+			stdout(simctl.keychain_add_cert(&device_name, "cert.der").unwrap()),
+			"simctl keychain iPhone 15 Pro add-cert cert.der\n"
+		);
+		assert_eq!(
+			stdout(simctl.keychain_reset(&device_name).unwrap()),
+			"simctl keychain iPhone 15 Pro reset\n"
+		);
+	}
+}
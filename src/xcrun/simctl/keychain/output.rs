@@ -0,0 +1,84 @@
+use crate::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum KeychainOutput {
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	/// The certificate file couldn't be read as a certificate, e.g.
+	/// `Unable to read certificate at path ca.txt`
+	ErrorInvalidCertificate { stderr: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for KeychainOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn errored_from_str(input: &str) -> Self {
+		if input.contains("Unable to read certificate") {
+			let output = Self::ErrorInvalidCertificate {
+				stderr: input.to_owned(),
+			};
+			warn!(?output, "Parsed xcrun simctl keychain error");
+			return output;
+		}
+		Self::error_unimplemented(input.to_owned())
+	}
+}
+
+impl PublicCommandOutput for KeychainOutput {
+	type PrimarySuccess = ();
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			KeychainOutput::SuccessUnImplemented { .. } => Ok(&()),
+			KeychainOutput::ErrorInvalidCertificate { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Certificates must be PEM or DER encoded",
+			)),
+			KeychainOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn keychain_output() {
+		assert!(KeychainOutput::success_from_str("").success().is_ok());
+
+		let output = KeychainOutput::errored_from_str("Unable to read certificate at path ca.txt\n");
+		assert!(matches!(
+			output,
+			KeychainOutput::ErrorInvalidCertificate { .. }
+		));
+		match output.success() {
+			Err(Error::OutputErrored {
+				help_hint: Some(hint),
+				..
+			}) => assert!(hint.contains("PEM or DER"), "{}", hint),
+			result => panic!("Expected an error with a hint, got {:?}", result),
+		}
+
+		let output = KeychainOutput::errored_from_str("Invalid device: booted\n");
+		assert!(matches!(
+			output.success(),
+			Err(Error::OutputErrored {
+				help_hint: None,
+				..
+			})
+		));
+	}
+}
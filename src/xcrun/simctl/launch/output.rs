@@ -4,6 +4,27 @@ use crate::prelude::*;
 #[non_exhaustive]
 #[must_use = include_doc!(must_use_cmd_output)]
 pub enum LaunchOutput {
+	/// The app was launched, and `simctl` reported the PID of the new process:
+	/// ```text
+	/// com.example.app: 12345
+	/// ```
+	Launched { bundle_id: String, pid: u32 },
+
+	/// The bundle identifier isn't installed on the simulator:
+	/// ```text
+	/// An error was encountered processing the command (domain=FBSOpenApplicationServiceErrorDomain, code=4):
+	/// The request to open "com.example.app" failed.
+	/// ```
+	ErrorAppNotInstalled { error: NSError },
+
+	/// The simulator isn't booted:
+	/// ```text
+	/// An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):
+	/// Unable to lookup in current state: Shutdown
+	/// ```
+	/// Any state other than `Booted`, e.g. `Shutting Down`
+	ErrorDeviceNotBooted { error: NSError },
+
 	#[doc = include_doc!(cmd_error)]
 	ErrorUnImplemented(String),
 
@@ -11,6 +32,24 @@ pub enum LaunchOutput {
 	SuccessUnImplemented(String),
 }
 
+impl LaunchOutput {
+	/// The PID of the launched process, if it was parsed
+	pub fn pid(&self) -> Option<u32> {
+		match self {
+			LaunchOutput::Launched { pid, .. } => Some(*pid),
+			_ => None,
+		}
+	}
+
+	/// The bundle identifier of the launched process, if it was parsed
+	pub fn bundle_id(&self) -> Option<&str> {
+		match self {
+			LaunchOutput::Launched { bundle_id, .. } => Some(bundle_id),
+			_ => None,
+		}
+	}
+}
+
 impl CommandNomParsable for LaunchOutput {
 	fn success_unimplemented(str: String) -> Self {
 		Self::SuccessUnImplemented(str)
@@ -19,6 +58,14 @@ impl CommandNomParsable for LaunchOutput {
 	fn error_unimplemented(str: String) -> Self {
 		Self::ErrorUnImplemented(str)
 	}
+
+	fn success_nom_from_str(input: &str) -> IResult<&str, Self> {
+		parse_launched(input)
+	}
+
+	fn errored_nom_from_str(input: &str) -> IResult<&str, Self> {
+		parse_launch_error(input)
+	}
 }
 
 impl PublicCommandOutput for LaunchOutput {
@@ -26,8 +73,95 @@ impl PublicCommandOutput for LaunchOutput {
 
 	fn success(&self) -> Result<&Self::PrimarySuccess> {
 		match self {
-			LaunchOutput::SuccessUnImplemented(_) => Ok(&()),
+			LaunchOutput::Launched { .. } | LaunchOutput::SuccessUnImplemented(_) => Ok(&()),
+			LaunchOutput::ErrorAppNotInstalled { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Install the app first, e.g. `apple-clis xcrun simctl install --booted --glob`",
+			)),
+			LaunchOutput::ErrorDeviceNotBooted { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Boot the simulator first with `apple-clis xcrun simctl boot`, or pass --auto-boot",
+			)),
 			LaunchOutput::ErrorUnImplemented(_) => Err(Error::output_errored(self)),
 		}
 	}
 }
+
+/// Parses [LaunchOutput::Launched]
+/// com.example.app: 12345
+fn parse_launched(input: &str) -> IResult<&str, LaunchOutput> {
+	let (remaining, bundle_id) = ws(take_till1(|c: char| c == ':' || c.is_whitespace()))(input)?;
+	let (remaining, pid) = preceded(tag(":"), ws(u32::nom_from_str))(remaining)?;
+	Ok((
+		remaining,
+		LaunchOutput::Launched {
+			bundle_id: bundle_id.to_owned(),
+			pid,
+		},
+	))
+}
+
+/// Parses [LaunchOutput::ErrorAppNotInstalled] and [LaunchOutput::ErrorDeviceNotBooted]
+fn parse_launch_error(input: &str) -> IResult<&str, LaunchOutput> {
+	map_opt(NSError::nom_from_str, |error| {
+		let output = if error.any_is("FBSOpenApplicationServiceErrorDomain", 4)
+			|| error.any_is("FBSOpenApplicationErrorDomain", 4)
+		{
+			LaunchOutput::ErrorAppNotInstalled { error }
+		} else if error.invalid_state().is_some_and(|state| state != "Booted") {
+			LaunchOutput::ErrorDeviceNotBooted { error }
+		} else {
+			return None;
+		};
+		warn!(?output, "Parsed xcrun simctl launch error");
+		Some(output)
+	})(input)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_launched() {
+		let output = LaunchOutput::success_from_str("com.example.app: 12345\n");
+		match output {
+			LaunchOutput::Launched { bundle_id, pid } => {
+				assert_eq!(bundle_id, "com.example.app");
+				assert_eq!(pid, 12345);
+			}
+			_ => panic!("Expected LaunchOutput::Launched, got {:?}", output),
+		}
+	}
+
+	#[test]
+	fn parses_errors() {
+		let not_installed = r#"An error was encountered processing the command (domain=FBSOpenApplicationServiceErrorDomain, code=4):
+The request to open "com.example.app" failed.
+Underlying error (domain=FBSOpenApplicationErrorDomain, code=4):
+	The operation couldn’t be completed. Application info provider (FBSApplicationLibrary) returned nil for "com.example.app"
+"#;
+		let output = LaunchOutput::errored_from_str(not_installed);
+		assert!(
+			matches!(output, LaunchOutput::ErrorAppNotInstalled { .. }),
+			"{:?}",
+			output
+		);
+
+		let not_booted = "An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):\nUnable to lookup in current state: Shutdown\n";
+		let output = LaunchOutput::errored_from_str(not_booted);
+		assert!(
+			matches!(output, LaunchOutput::ErrorDeviceNotBooted { .. }),
+			"{:?}",
+			output
+		);
+
+		let booted = "An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):\nUnable to lookup in current state: Booted\n";
+		let output = LaunchOutput::errored_from_str(booted);
+		assert!(
+			matches!(output, LaunchOutput::ErrorUnImplemented(_)),
+			"{:?}",
+			output
+		);
+	}
+}
@@ -97,11 +97,76 @@ impl ListJson {
 	pub fn devices(&self) -> impl Iterator<Item = &ListDevice> + '_ {
 		self.devices.values().flatten()
 	}
+
+	/// Returns an iterator over the returned devices, with the runtime each is created with
+	pub fn devices_with_runtime(
+		&self,
+	) -> impl Iterator<Item = (&RuntimeIdentifier, &ListDevice)> + '_ {
+		self
+			.devices
+			.iter()
+			.flat_map(|(runtime, devices)| devices.iter().map(move |device| (runtime, device)))
+	}
+
+	/// Installed runtimes, including unavailable ones
+	pub fn runtimes(&self) -> &[ListRuntime] {
+		&self.runtimes
+	}
+
+	/// Device types that simulators can be created with
+	pub fn device_types(&self) -> &[ListDeviceType] {
+		&self.devicetypes
+	}
 }
 
-#[derive(Deserialize, Debug, Serialize)]
+#[derive(Deserialize, Debug, Clone, Serialize)]
 pub struct ListJson {
 	devices: HashMap<RuntimeIdentifier, Vec<ListDevice>>,
+
+	/// Missing from `simctl list devices --json`
+	#[serde(default)]
+	runtimes: Vec<ListRuntime>,
+
+	/// Missing from `simctl list devices --json`
+	#[serde(default)]
+	devicetypes: Vec<ListDeviceType>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct ListRuntime {
+	pub identifier: RuntimeIdentifier,
+	/// E.g. "iOS 17.4"
+	pub name: String,
+	/// E.g. "17.4"
+	pub version: String,
+	#[serde(rename(deserialize = "buildversion"))]
+	pub build_version: String,
+	/// E.g. "iOS". Only included since Xcode 15
+	pub platform: Option<String>,
+	pub is_available: bool,
+}
+
+impl ListRuntime {
+	/// The numeric components of [ListRuntime::version], for comparing versions
+	pub fn version_components(&self) -> Vec<u32> {
+		self
+			.version
+			.split('.')
+			.map_while(|component| component.parse().ok())
+			.collect()
+	}
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct ListDeviceType {
+	/// E.g. "com.apple.CoreSimulator.SimDeviceType.iPhone-15-Pro"
+	pub identifier: String,
+	/// E.g. "iPhone 15 Pro"
+	pub name: String,
+	/// E.g. "iPhone" or "iPad"
+	pub product_family: Option<String>,
 }
 
 /// Allows for easier extraction of semantic information from
@@ -171,6 +236,13 @@ pub struct ListDevice {
 pub enum State {
 	Shutdown,
 	Booted,
+	Booting,
+	#[serde(rename = "Shutting Down")]
+	ShuttingDown,
+	Creating,
+	/// A state not known to this version of the crate
+	#[serde(other)]
+	Unknown,
 }
 
 impl State {
@@ -0,0 +1,243 @@
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use gpx::parse_gpx_waypoints;
+mod gpx;
+pub use output::*;
+mod output;
+
+/// A latitude and longitude in degrees.
+/// [Display]s and parses as `<latitude>,<longitude>`, which is the format `simctl` expects.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Coordinate {
+	pub latitude: f64,
+	pub longitude: f64,
+}
+
+impl_from_str_nom!(Coordinate);
+
+impl Coordinate {
+	pub fn new(latitude: f64, longitude: f64) -> Self {
+		Self {
+			latitude,
+			longitude,
+		}
+	}
+}
+
+impl NomFromStr for Coordinate {
+	fn nom_from_str(input: &str) -> IResult<&str, Self> {
+		map(
+			tuple((
+				ws(nom::number::complete::double),
+				tag(","),
+				ws(nom::number::complete::double),
+			)),
+			|(latitude, _, longitude)| Coordinate::new(latitude, longitude),
+		)(input)
+	}
+}
+
+impl Display for Coordinate {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{},{}", self.latitude, self.longitude)
+	}
+}
+
+/// How often a [LocationRoute] emits location updates
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RoutePacing {
+	/// Emit an update every this many meters travelled
+	Distance(f64),
+	/// Emit an update every this many seconds
+	Interval(f64),
+}
+
+/// A route for the simulator to travel along, between [LocationRoute::waypoints]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocationRoute {
+	pub waypoints: Vec<Coordinate>,
+	/// In meters per second, `simctl` defaults to 20m/s
+	pub speed: Option<f64>,
+	pub pacing: Option<RoutePacing>,
+}
+
+impl LocationRoute {
+	pub fn new(waypoints: Vec<Coordinate>) -> Self {
+		Self {
+			waypoints,
+			speed: None,
+			pacing: None,
+		}
+	}
+
+	/// Uses the track, route or waypoints of a GPX file, see [parse_gpx_waypoints]
+	pub fn from_gpx(gpx: &str) -> Result<Self> {
+		Ok(Self::new(parse_gpx_waypoints(gpx)?))
+	}
+
+	/// Returns [Error::LocationRouteTooShort] for less than two waypoints,
+	/// which `simctl location start` rejects
+	fn check_waypoints(&self) -> Result<()> {
+		if self.waypoints.len() < 2 {
+			return Err(Error::LocationRouteTooShort {
+				waypoints: self.waypoints.len(),
+			});
+		}
+		Ok(())
+	}
+
+	pub fn from_gpx_file(path: impl AsRef<Utf8Path>) -> Result<Self> {
+		let path = path.as_ref();
+		let gpx = std::fs::read_to_string(path).map_err(|err| Error::FileIo {
+			path: path.to_owned(),
+			err,
+		})?;
+		Self::from_gpx(&gpx)
+	}
+
+	pub fn with_speed(mut self, meters_per_second: f64) -> Self {
+		self.speed = Some(meters_per_second);
+		self
+	}
+
+	pub fn with_pacing(mut self, pacing: RoutePacing) -> Self {
+		self.pacing = Some(pacing);
+		self
+	}
+}
+
+impl XcRunSimctlInstance<'_> {
+	fn location_command(&self, device_name: &DeviceName) -> bossy::Command {
+		self
+			.bossy_command()
+			.with_arg("location")
+			.with_arg(device_name.to_string())
+	}
+
+	/// Sets a fixed simulated location
+	#[instrument(skip_all, ret, fields(%device_name, %coordinate))]
+	pub fn location_set(
+		&self,
+		device_name: &DeviceName,
+		coordinate: Coordinate,
+	) -> Result<LocationOutput> {
+		LocationOutput::from_bossy_result(
+			self
+				.location_command(device_name)
+				.with_arg("set")
+				.with_arg(coordinate.to_string())
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Stops any running location scenario, and clears the simulated location
+	#[instrument(skip_all, ret, fields(%device_name))]
+	pub fn location_clear(&self, device_name: &DeviceName) -> Result<LocationOutput> {
+		LocationOutput::from_bossy_result(
+			self
+				.location_command(device_name)
+				.with_arg("clear")
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Starts travelling along a route in the background.
+	/// Returns immediately, call [XcRunSimctlInstance::location_clear] to stop.
+	/// Returns [Error::LocationRouteTooShort] without running `simctl` for less than two waypoints
+	#[instrument(skip_all, ret, fields(%device_name, waypoints = route.waypoints.len()))]
+	pub fn location_run_route(
+		&self,
+		device_name: &DeviceName,
+		route: &LocationRoute,
+	) -> Result<LocationOutput> {
+		route.check_waypoints()?;
+		let mut command = self.location_command(device_name).with_arg("start");
+		if let Some(speed) = route.speed {
+			command.add_arg(format!("--speed={}", speed));
+		}
+		match route.pacing {
+			Some(RoutePacing::Distance(distance)) => {
+				command.add_arg(format!("--distance={}", distance));
+			}
+			Some(RoutePacing::Interval(interval)) => {
+				command.add_arg(format!("--interval={}", interval));
+			}
+			None => {}
+		}
+		command.add_args(route.waypoints.iter().map(Coordinate::to_string));
+		LocationOutput::from_bossy_result(command.run_and_wait_for_output())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::shared::assert_nom_parses;
+
+	use super::*;
+
+	#[test]
+	fn coordinate_hardcoded() {
+		let examples = ["-33.8568,151.2153", "37.33182,-122.03118", "0,0"];
+		assert_nom_parses::<Coordinate>(examples, |_| true);
+	}
+
+	#[test]
+	fn route_needs_two_waypoints() {
+		let sydney = Coordinate::new(-33.8568, 151.2153);
+		let cupertino = Coordinate::new(37.33182, -122.03118);
+		assert!(matches!(
+			LocationRoute::new(vec![sydney]).check_waypoints(),
+			Err(Error::LocationRouteTooShort { waypoints: 1 })
+		));
+		assert!(LocationRoute::new(vec![sydney, cupertino])
+			.check_waypoints()
+			.is_ok());
+	}
+
+	#[test]
+	fn location_args() {
+		let xcrun = super::super::echo_xcrun();
+		let simctl = xcrun.simctl();
+		let device_name = DeviceName::from_str("iPhone 15 Pro").unwrap();
+		let stdout = |output: LocationOutput| match output {
+			LocationOutput::SuccessUnImplemented { stdout } => stdout,
+			output => panic!("Unexpected output {:?}", output),
+		};
+		let sydney = Coordinate::new(-33.8568, 151.2153);
+		let cupertino = Coordinate::new(37.33182, -122.03118);
+
+		assert_eq!(
+			stdout(simctl.location_set(&device_name, sydney).unwrap()),
+			"simctl location iPhone 15 Pro set -33.8568,151.2153\n"
+		);
+		assert_eq!(
+			stdout(simctl.location_clear(&device_name).unwrap()),
+			"simctl location iPhone 15 Pro clear\n"
+		);
+
+		let route = LocationRoute::new(vec![sydney, cupertino]);
+		assert_eq!(
+			stdout(simctl.location_run_route(&device_name, &route).unwrap()),
+			"simctl location iPhone 15 Pro start -33.8568,151.2153 37.33182,-122.03118\n"
+		);
+		let route = route.with_speed(20.0);
+		assert_eq!(
+			stdout(
+				simctl
+					.location_run_route(&device_name, &route.clone().with_pacing(RoutePacing::Distance(5.0)))
+					.unwrap()
+			),
+			"simctl location iPhone 15 Pro start --speed=20 --distance=5 -33.8568,151.2153 37.33182,-122.03118\n"
+		);
+		assert_eq!(
+			stdout(
+				simctl
+					.location_run_route(&device_name, &route.with_pacing(RoutePacing::Interval(0.5)))
+					.unwrap()
+			),
+			"simctl location iPhone 15 Pro start --speed=20 --interval=0.5 -33.8568,151.2153 37.33182,-122.03118\n"
+		);
+	}
+}
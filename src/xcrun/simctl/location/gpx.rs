@@ -0,0 +1,120 @@
+use crate::prelude::*;
+
+use super::Coordinate;
+
+/// Extracts the waypoints of a GPX file.
+///
+/// Track points (`<trkpt>`) are preferred, then route points (`<rtept>`),
+/// then standalone waypoints (`<wpt>`).
+/// Only the `lat` and `lon` attributes are read, everything else is ignored.
+pub fn parse_gpx_waypoints(gpx: &str) -> Result<Vec<Coordinate>> {
+	for element in ["trkpt", "rtept", "wpt"] {
+		let waypoints = points(gpx, element)?;
+		if !waypoints.is_empty() {
+			debug!(element, count = waypoints.len(), "Parsed GPX waypoints");
+			return Ok(waypoints);
+		}
+	}
+	Err(Error::GpxWithoutWaypoints)
+}
+
+fn points(gpx: &str, element: &str) -> Result<Vec<Coordinate>> {
+	let open = format!("<{}", element);
+	let mut remaining = gpx;
+	let mut points = Vec::new();
+	while let Some(start) = remaining.find(&open) {
+		let (after, attributes) =
+			element_attributes(&remaining[start + open.len()..]).map_err(|err| {
+				Error::NomParsingFailed {
+					name: format!("GPX <{}> element", element),
+					err: err.to_owned(),
+				}
+			})?;
+		remaining = after;
+		// skips elements like <wptx> that only share a prefix
+		if !attributes.starts_with(|c: char| c.is_whitespace()) {
+			continue;
+		}
+		match (attribute(attributes, "lat"), attribute(attributes, "lon")) {
+			(Some(latitude), Some(longitude)) => points.push(Coordinate::new(latitude, longitude)),
+			_ => warn!(
+				attributes,
+				"Skipping GPX <{}> element without lat and lon", element
+			),
+		}
+	}
+	Ok(points)
+}
+
+/// Everything up to the closing `>` of the opening tag
+fn element_attributes(input: &str) -> IResult<&str, &str> {
+	terminated(take_till(|c| c == '>'), tag(">"))(input)
+}
+
+/// Finds `name="value"` or `name='value'` and parses the value
+fn attribute(attributes: &str, name: &str) -> Option<f64> {
+	let mut remaining = attributes;
+	while let Some(start) = remaining.find(name) {
+		let preceded_by_whitespace = remaining[..start]
+			.chars()
+			.last()
+			.map_or(true, char::is_whitespace);
+		remaining = &remaining[start + name.len()..];
+		if !preceded_by_whitespace {
+			continue;
+		}
+		let value: IResult<&str, f64> = preceded(
+			ws(tag("=")),
+			alt((
+				delimited(tag("\""), ws(nom::number::complete::double), tag("\"")),
+				delimited(tag("'"), ws(nom::number::complete::double), tag("'")),
+			)),
+		)(remaining);
+		if let Ok((_, value)) = value {
+			return Some(value);
+		}
+	}
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_track() {
+		let gpx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="apple-clis">
+	<wpt lat="1" lon="1"><name>Ignored, since there is a track</name></wpt>
+	<trk>
+		<trkseg>
+			<trkpt lat="-33.8568" lon="151.2153"><ele>4</ele></trkpt>
+			<trkpt lon='151.2093' lat='-33.8688'/>
+		</trkseg>
+	</trk>
+</gpx>
+"#;
+		assert_eq!(
+			parse_gpx_waypoints(gpx).unwrap(),
+			vec![
+				Coordinate::new(-33.8568, 151.2153),
+				Coordinate::new(-33.8688, 151.2093)
+			]
+		);
+	}
+
+	#[test]
+	fn parses_waypoints() {
+		let gpx =
+			r#"<gpx><wpt lat="37.33182" lon="-122.03118"/><wpt lat="37.3349" lon="-122.0090"/></gpx>"#;
+		assert_eq!(parse_gpx_waypoints(gpx).unwrap().len(), 2);
+	}
+
+	#[test]
+	fn errors_without_waypoints() {
+		assert!(matches!(
+			parse_gpx_waypoints("<gpx></gpx>"),
+			Err(Error::GpxWithoutWaypoints)
+		));
+	}
+}
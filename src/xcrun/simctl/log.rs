@@ -0,0 +1,256 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Child, ChildStdout, Stdio};
+
+use crate::prelude::*;
+
+use super::spawn::SpawnCommand;
+use super::XcRunSimctlInstance;
+
+pub use event::*;
+mod event;
+pub use output::*;
+mod output;
+
+/// Builds a `log` predicate, combining every filter with `AND`.
+/// See `log help predicates` for the full syntax
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogPredicate {
+	clauses: Vec<String>,
+}
+
+impl LogPredicate {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	fn quoted(value: &str) -> String {
+		format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+	}
+
+	/// Only events from the given `os_log` subsystem, e.g. `com.example.app`
+	pub fn with_subsystem(mut self, subsystem: impl AsRef<str>) -> Self {
+		self
+			.clauses
+			.push(format!("subsystem == {}", Self::quoted(subsystem.as_ref())));
+		self
+	}
+
+	/// Only events from the given `os_log` category
+	pub fn with_category(mut self, category: impl AsRef<str>) -> Self {
+		self
+			.clauses
+			.push(format!("category == {}", Self::quoted(category.as_ref())));
+		self
+	}
+
+	/// Only events from the process with the given executable name
+	pub fn with_process(mut self, process: impl AsRef<str>) -> Self {
+		self
+			.clauses
+			.push(format!("process == {}", Self::quoted(process.as_ref())));
+		self
+	}
+
+	/// Adds a raw predicate clause, e.g. `eventMessage CONTAINS "error"`
+	pub fn with_raw(mut self, clause: impl Into<String>) -> Self {
+		self.clauses.push(clause.into());
+		self
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.clauses.is_empty()
+	}
+}
+
+impl Display for LogPredicate {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.clauses.join(" AND "))
+	}
+}
+
+/// The time range passed to `log show`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogShowRange {
+	/// E.g. `5m`, `2h` or `1d`
+	Last(String),
+	/// Dates in the format `log` accepts, e.g. `2024-03-01 12:00:00`
+	Between { start: String, end: Option<String> },
+}
+
+impl LogShowRange {
+	fn args(&self) -> Vec<String> {
+		match self {
+			LogShowRange::Last(last) => vec!["--last".into(), last.clone()],
+			LogShowRange::Between { start, end } => {
+				let mut args = vec!["--start".into(), start.clone()];
+				if let Some(end) = end {
+					args.push("--end".into());
+					args.push(end.clone());
+				}
+				args
+			}
+		}
+	}
+}
+
+/// A running `log stream` inside a simulator.
+/// Iterate to receive each [LogEvent] as it is logged.
+/// The stream is killed when dropped.
+#[derive(Debug)]
+pub struct LogStream {
+	child: Child,
+	lines: std::io::Lines<BufReader<ChildStdout>>,
+	level: LogLevel,
+}
+
+impl Iterator for LogStream {
+	type Item = Result<LogEvent>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			match self.lines.next()? {
+				Ok(line) => match LogEvent::from_ndjson_line(&line) {
+					Some(event) if self.level.includes(event.level) => return Some(Ok(event)),
+					Some(event) => trace!(?event, "Skipping event below the requested level"),
+					// e.g. "Filtering the log data using ..."
+					None => trace!(?line, "Skipping non-event line in log stream"),
+				},
+				Err(err) => return Some(Err(Error::ChildProcessIo(err))),
+			}
+		}
+	}
+}
+
+impl Drop for LogStream {
+	fn drop(&mut self) {
+		if let Err(err) = self
+			.child
+			.kill()
+			.and_then(|_| self.child.wait().map(|_| ()))
+		{
+			warn!(?err, "Failed to kill log stream");
+		}
+	}
+}
+
+impl XcRunSimctlInstance<'_> {
+	fn log_command(action: &str, predicate: &LogPredicate, level: LogLevel) -> SpawnCommand {
+		let mut command = SpawnCommand::new("log").with_args([action, "--style", "ndjson"]);
+		if let Some(level) = level.as_level_arg() {
+			command = command.with_args(["--level", level]);
+		}
+		let predicate = match level.as_predicate_clause() {
+			Some(clause) => predicate.clone().with_raw(clause),
+			None => predicate.clone(),
+		};
+		if !predicate.is_empty() {
+			command = command
+				.with_arg("--predicate")
+				.with_arg(predicate.to_string());
+		}
+		command
+	}
+
+	/// Streams log events from a booted simulator as they happen.
+	/// `level` is the most verbose level included, e.g. [LogLevel::Debug] includes everything
+	/// and [LogLevel::Error] only errors and faults
+	#[instrument(skip_all, fields(%device_name, %predicate))]
+	pub fn log_stream(
+		&self,
+		device_name: &DeviceName,
+		predicate: &LogPredicate,
+		level: LogLevel,
+	) -> Result<LogStream> {
+		let mut child = self
+			.spawn_std_command(device_name, &Self::log_command("stream", predicate, level))
+			.stdin(Stdio::null())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::inherit())
+			.spawn()
+			.map_err(Error::ChildProcessIo)?;
+		let stdout = child
+			.stdout
+			.take()
+			.expect("stdout of log stream to be piped");
+		Ok(LogStream {
+			child,
+			lines: BufReader::new(stdout).lines(),
+			level,
+		})
+	}
+
+	/// Shows log events from a simulator in a past time range.
+	/// `level` is the most verbose level included, as for [XcRunSimctlInstance::log_stream]
+	#[instrument(skip_all, fields(%device_name, %predicate, ?range))]
+	pub fn log_show(
+		&self,
+		device_name: &DeviceName,
+		predicate: &LogPredicate,
+		level: LogLevel,
+		range: &LogShowRange,
+	) -> Result<LogShowOutput> {
+		let output = LogShowOutput::from_bossy_result(
+			self
+				.spawn_bossy_command(
+					device_name,
+					&Self::log_command("show", predicate, level).with_args(range.args()),
+				)
+				.run_and_wait_for_output(),
+		)?;
+		Ok(output.with_min_level(level))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn predicate_builder() {
+		let predicate = LogPredicate::new()
+			.with_subsystem("com.example.app")
+			.with_process("Example \"App\"");
+		assert_eq!(
+			predicate.to_string(),
+			r#"subsystem == "com.example.app" AND process == "Example \"App\"""#
+		);
+	}
+
+	#[test]
+	fn level_args() {
+		let predicate = LogPredicate::new().with_subsystem("com.example.app");
+		let device_name = DeviceName::from_str("iPhone 15").unwrap();
+		let args = |level| {
+			XcRunSimctlInstance::log_command("stream", &predicate, level).simctl_args(&device_name)
+		};
+
+		let debug = args(LogLevel::Debug);
+		assert!(debug.windows(2).any(|args| args == ["--level", "debug"]));
+		assert!(debug.contains(&r#"subsystem == "com.example.app""#.to_owned()));
+
+		let error = args(LogLevel::Error);
+		assert!(!error.contains(&"--level".to_owned()));
+		assert!(error.contains(
+			&r#"subsystem == "com.example.app" AND (messageType == error OR messageType == fault)"#
+				.to_owned()
+		));
+	}
+
+	#[test]
+	fn filters_events_by_level() {
+		let stdout = ["Debug", "Info", "Default", "Error", "Fault"]
+			.map(|level| format!(r#"{{"timestamp":"2024-03-01 12:34:56.789012+1100","messageType":"{level}","eventMessage":"{level}"}}"#))
+			.join("\n");
+		let levels =
+			|min_level| match LogShowOutput::success_from_str(&stdout).with_min_level(min_level) {
+				LogShowOutput::Events { events } => events
+					.into_iter()
+					.map(|event| event.level)
+					.collect::<Vec<_>>(),
+				output => panic!("Unexpected output {:?}", output),
+			};
+		assert_eq!(levels(LogLevel::Debug).len(), 5);
+		assert_eq!(levels(LogLevel::Error), [LogLevel::Error, LogLevel::Fault]);
+		assert_eq!(levels(LogLevel::Fault), [LogLevel::Fault]);
+	}
+}
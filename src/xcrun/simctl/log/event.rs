@@ -0,0 +1,150 @@
+use time::macros::format_description;
+
+use crate::prelude::*;
+
+/// The `messageType` of a log event, from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum LogLevel {
+	Debug,
+	Info,
+	Default,
+	Error,
+	Fault,
+}
+
+impl LogLevel {
+	/// The `--level` passed to `log`, which only accepts `default`, `info` and `debug`
+	pub(super) fn as_level_arg(&self) -> Option<&'static str> {
+		match self {
+			LogLevel::Debug => Some("debug"),
+			LogLevel::Info => Some("info"),
+			// `default` is what `log` uses when no `--level` is passed
+			_ => None,
+		}
+	}
+
+	/// A predicate clause for levels `--level` can't express, e.g. only errors and faults
+	pub(super) fn as_predicate_clause(&self) -> Option<&'static str> {
+		match self {
+			LogLevel::Error => Some("(messageType == error OR messageType == fault)"),
+			LogLevel::Fault => Some("messageType == fault"),
+			_ => None,
+		}
+	}
+
+	/// Whether an event at `level` is at least as severe as `self`
+	pub fn includes(&self, level: LogLevel) -> bool {
+		level >= *self
+	}
+}
+
+/// A single event from `log stream --style ndjson` or `log show --style ndjson`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LogEvent {
+	/// E.g. `2024-03-01 12:34:56.789012+1100`, see [LogEvent::parsed_timestamp]
+	pub timestamp: String,
+	/// The executable name of the logging process
+	pub process: String,
+	pub pid: u32,
+	/// Empty for events not logged with an `os_log` subsystem, e.g. `print`s to stderr
+	pub subsystem: String,
+	pub category: String,
+	pub level: LogLevel,
+	pub message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawLogEvent {
+	timestamp: String,
+	#[serde(default)]
+	process_image_path: String,
+	#[serde(rename = "processID", default)]
+	process_id: u32,
+	#[serde(default)]
+	subsystem: String,
+	#[serde(default)]
+	category: String,
+	message_type: Option<LogLevel>,
+	#[serde(default)]
+	event_message: String,
+}
+
+impl From<RawLogEvent> for LogEvent {
+	fn from(raw: RawLogEvent) -> Self {
+		let process = Utf8Path::new(&raw.process_image_path)
+			.file_name()
+			.unwrap_or_default()
+			.to_owned();
+		LogEvent {
+			timestamp: raw.timestamp,
+			process,
+			pid: raw.process_id,
+			subsystem: raw.subsystem,
+			category: raw.category,
+			level: raw.message_type.unwrap_or(LogLevel::Default),
+			message: raw.event_message,
+		}
+	}
+}
+
+impl LogEvent {
+	/// Parses one line of `--style ndjson` output.
+	/// Returns [None] for lines that aren't events, e.g. `Filtering the log data using ...`
+	pub fn from_ndjson_line(line: &str) -> Option<Self> {
+		let line = line.trim();
+		if !line.starts_with('{') {
+			return None;
+		}
+		match serde_json::from_str::<RawLogEvent>(line) {
+			Ok(raw) => Some(raw.into()),
+			Err(err) => {
+				debug!(?err, ?line, "Couldn't parse log event");
+				None
+			}
+		}
+	}
+
+	pub fn parsed_timestamp(&self) -> Result<time::OffsetDateTime> {
+		let format = format_description!(
+			"[year]-[month]-[day] [hour]:[minute]:[second].[subsecond][offset_hour sign:mandatory][offset_minute]"
+		);
+		Ok(time::OffsetDateTime::parse(&self.timestamp, &format)?)
+	}
+}
+
+impl Display for LogEvent {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} {}[{}]", self.timestamp, self.process, self.pid)?;
+		if !self.subsystem.is_empty() {
+			write!(f, " ({}:{})", self.subsystem, self.category)?;
+		}
+		write!(f, " {:?}: {}", self.level, self.message)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_ndjson_event() {
+		let line = r#"{"traceID":1234,"eventMessage":"Connected to server","eventType":"logEvent","source":null,"formatString":"Connected to %{public}s","activityIdentifier":0,"subsystem":"com.example.app","category":"network","threadID":5678,"senderImageUUID":"00000000-0000-0000-0000-000000000000","bootUUID":"","processImagePath":"\/Users\/me\/Library\/Developer\/CoreSimulator\/Devices\/ABC\/data\/Containers\/Bundle\/Application\/DEF\/Example.app\/Example","senderImagePath":"\/usr\/lib\/system\/libsystem_trace.dylib","timestamp":"2024-03-01 12:34:56.789012+1100","machTimestamp":123456789,"messageType":"Error","processImageUUID":"00000000-0000-0000-0000-000000000000","processID":4321,"senderProgramCounter":1234,"parentActivityIdentifier":0,"timezoneName":""}"#;
+		let event = LogEvent::from_ndjson_line(line).unwrap();
+		assert_eq!(event.process, "Example");
+		assert_eq!(event.pid, 4321);
+		assert_eq!(event.subsystem, "com.example.app");
+		assert_eq!(event.category, "network");
+		assert_eq!(event.level, LogLevel::Error);
+		assert_eq!(event.message, "Connected to server");
+		let timestamp = event.parsed_timestamp().unwrap();
+		assert_eq!(timestamp.microsecond(), 789012);
+		assert_eq!(timestamp.offset().whole_hours(), 11);
+
+		assert!(LogEvent::from_ndjson_line(
+			"Filtering the log data using \"subsystem == \\\"com.example.app\\\"\""
+		)
+		.is_none());
+	}
+}
@@ -0,0 +1,85 @@
+use crate::prelude::*;
+
+use super::{LogEvent, LogLevel};
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum LogShowOutput {
+	/// Every parsed event, in the order `log show` printed them
+	Events { events: Vec<LogEvent> },
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl LogShowOutput {
+	/// Drops events less severe than `level`, since `log --level` only filters
+	/// down to [LogLevel::Default]
+	pub(super) fn with_min_level(self, level: LogLevel) -> Self {
+		match self {
+			Self::Events { mut events } => {
+				events.retain(|event| level.includes(event.level));
+				Self::Events { events }
+			}
+			output => output,
+		}
+	}
+}
+
+impl CommandNomParsable for LogShowOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	/// Parses `--style ndjson` output, skipping lines that aren't events
+	fn success_from_str(input: &str) -> Self {
+		let events: Vec<LogEvent> = input
+			.lines()
+			.filter_map(LogEvent::from_ndjson_line)
+			.collect();
+		if events.is_empty() && input.lines().any(|line| line.trim_start().starts_with('{')) {
+			error!("Failed to parse any log events");
+			return Self::success_unimplemented(input.to_owned());
+		}
+		Self::Events { events }
+	}
+}
+
+impl PublicCommandOutput for LogShowOutput {
+	type PrimarySuccess = Vec<LogEvent>;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			LogShowOutput::Events { events } => Ok(events),
+			_ => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_ndjson() {
+		let stdout = "Filtering the log data using \"subsystem == \\\"com.example\\\"\"\n\
+			{\"timestamp\":\"2024-03-01 12:34:56.789012+1100\",\"messageType\":\"Error\",\"eventMessage\":\"Failed\"}\n";
+		let output = LogShowOutput::success_from_str(stdout);
+		assert_eq!(output.success().unwrap().len(), 1);
+
+		let output = LogShowOutput::success_from_str("Filtering the log data\n");
+		assert!(output.success().unwrap().is_empty());
+
+		let output = LogShowOutput::success_from_str("{\"not\": \"an event\"\n");
+		assert!(matches!(output, LogShowOutput::SuccessUnImplemented { .. }));
+		assert!(output.success().is_err());
+	}
+}
@@ -0,0 +1,46 @@
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+
+impl XcRunSimctlInstance<'_> {
+	/// Opens a URL in the simulator, as if it was tapped on the device.
+	/// Works with universal links and custom URL schemes.
+	#[instrument(skip_all, ret, fields(%device_name, url = url.as_ref()))]
+	pub fn open_url(&self, device_name: &DeviceName, url: impl AsRef<str>) -> Result<OpenUrlOutput> {
+		OpenUrlOutput::from_bossy_result(
+			self
+				.bossy_command()
+				.with_arg("openurl")
+				.with_arg(device_name.to_string())
+				.with_arg(url.as_ref())
+				.run_and_wait_for_output(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn open_url_args() {
+		let xcrun = super::super::echo_xcrun();
+		let device_name = DeviceName::from_str("iPhone 15 Pro").unwrap();
+		let output = xcrun
+			.simctl()
+			.open_url(&device_name, "example://path?query=1")
+			.unwrap();
+		match output {
+			OpenUrlOutput::SuccessUnImplemented { stdout } => {
+				assert_eq!(
+					stdout,
+					"simctl openurl iPhone 15 Pro example://path?query=1\n"
+				)
+			}
+			output => panic!("Unexpected output {:?}", output),
+		}
+	}
+}
@@ -0,0 +1,73 @@
+use crate::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum OpenUrlOutput {
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	/// No app installed on the simulator handles the URL's scheme:
+	/// ```text
+	/// An error was encountered processing the command (domain=NSOSStatusErrorDomain, code=-10814):
+	/// Simulator device failed to open example://.
+	/// ```
+	ErrorNoHandler { error: NSError },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for OpenUrlOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn errored_nom_from_str(input: &str) -> IResult<&str, Self> {
+		map_opt(NSError::nom_from_str, |error| {
+			error
+				.any_is("NSOSStatusErrorDomain", -10814)
+				.then_some(OpenUrlOutput::ErrorNoHandler { error })
+		})(input)
+	}
+}
+
+impl PublicCommandOutput for OpenUrlOutput {
+	type PrimarySuccess = ();
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			OpenUrlOutput::SuccessUnImplemented { .. } => Ok(&()),
+			OpenUrlOutput::ErrorNoHandler { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Install an app that handles this URL scheme (or associated domain) on the simulator first",
+			)),
+			OpenUrlOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_no_handler() {
+		let no_handler =
+			"An error was encountered processing the command (domain=NSOSStatusErrorDomain, code=-10814):
+Simulator device failed to open example://.
+Underlying error (domain=NSOSStatusErrorDomain, code=-10814):
+	The operation couldn’t be completed. (OSStatus error -10814.)
+";
+		let output = OpenUrlOutput::errored_from_str(no_handler);
+		assert!(
+			matches!(output, OpenUrlOutput::ErrorNoHandler { .. }),
+			"{:?}",
+			output
+		);
+	}
+}
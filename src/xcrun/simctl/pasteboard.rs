@@ -0,0 +1,59 @@
+use std::io::Write as _;
+
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+
+impl XcRunSimctlInstance<'_> {
+	/// Copies `contents` to the simulator's pasteboard
+	#[instrument(skip_all, ret, fields(%device_name, len = contents.as_ref().len()))]
+	pub fn pbcopy(
+		&self,
+		device_name: &DeviceName,
+		contents: impl AsRef<str>,
+	) -> Result<PbcopyOutput> {
+		let mut handle = self
+			.bossy_command()
+			.with_arg("pbcopy")
+			.with_arg(device_name.to_string())
+			.with_stdin_piped()
+			.with_stdout_piped()
+			.with_stderr_piped()
+			.run()?;
+		if let Some(stdin) = handle.stdin() {
+			stdin
+				.write_all(contents.as_ref().as_bytes())
+				.map_err(Error::ChildProcessIo)?;
+		}
+		// stdin is closed before waiting, so `pbcopy` sees EOF
+		PbcopyOutput::from_bossy_result(handle.wait_for_output())
+	}
+
+	/// Reads the contents of the simulator's pasteboard
+	#[instrument(skip_all, ret, fields(%device_name))]
+	pub fn pbpaste(&self, device_name: &DeviceName) -> Result<PbpasteOutput> {
+		PbpasteOutput::from_bossy_result(
+			self
+				.bossy_command()
+				.with_arg("pbpaste")
+				.with_arg(device_name.to_string())
+				.run_and_wait_for_output(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn pbpaste_args() {
+		let xcrun = super::super::echo_xcrun();
+		let device_name = DeviceName::from_str("iPhone 15 Pro").unwrap();
+		let output = xcrun.simctl().pbpaste(&device_name).unwrap();
+		assert_eq!(output.success().unwrap(), "simctl pbpaste iPhone 15 Pro\n");
+	}
+}
@@ -0,0 +1,86 @@
+use crate::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum PbcopyOutput {
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for PbcopyOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+}
+
+impl PublicCommandOutput for PbcopyOutput {
+	type PrimarySuccess = ();
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			PbcopyOutput::SuccessUnImplemented { .. } => Ok(&()),
+			PbcopyOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum PbpasteOutput {
+	/// The pasteboard contents, exactly as printed by `simctl pbpaste`
+	Contents { contents: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for PbpasteOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::Contents { contents: stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+}
+
+impl PublicCommandOutput for PbpasteOutput {
+	type PrimarySuccess = String;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			PbpasteOutput::Contents { contents } => Ok(contents),
+			PbpasteOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn pbpaste_keeps_contents_exactly() {
+		let contents = "  line one\nline two\n\n";
+		let output = PbpasteOutput::success_from_str(contents);
+		assert_eq!(output.success().unwrap(), contents);
+
+		let output = PbpasteOutput::errored_from_str("Invalid device: iPhone 99");
+		assert!(output.failed());
+	}
+
+	#[test]
+	fn pbcopy_output() {
+		assert!(PbcopyOutput::success_from_str("").success().is_ok());
+		assert!(PbcopyOutput::errored_from_str("Invalid device: iPhone 99").failed());
+	}
+}
@@ -0,0 +1,177 @@
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+
+/// Services whose permissions can be managed with `simctl privacy`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum PrivacyService {
+	/// Apply the action to all services
+	All,
+	Calendar,
+	ContactsLimited,
+	Contacts,
+	Location,
+	LocationAlways,
+	PhotosAdd,
+	Photos,
+	MediaLibrary,
+	Microphone,
+	Motion,
+	Reminders,
+	Siri,
+	/// Not supported by every Xcode version, in which case `simctl` will error
+	Camera,
+}
+
+impl PrivacyService {
+	fn as_arg(&self) -> &'static str {
+		match self {
+			PrivacyService::All => "all",
+			PrivacyService::Calendar => "calendar",
+			PrivacyService::ContactsLimited => "contacts-limited",
+			PrivacyService::Contacts => "contacts",
+			PrivacyService::Location => "location",
+			PrivacyService::LocationAlways => "location-always",
+			PrivacyService::PhotosAdd => "photos-add",
+			PrivacyService::Photos => "photos",
+			PrivacyService::MediaLibrary => "media-library",
+			PrivacyService::Microphone => "microphone",
+			PrivacyService::Motion => "motion",
+			PrivacyService::Reminders => "reminders",
+			PrivacyService::Siri => "siri",
+			PrivacyService::Camera => "camera",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PrivacyAction {
+	Grant,
+	Revoke,
+	Reset,
+}
+
+impl PrivacyAction {
+	fn as_arg(&self) -> &'static str {
+		match self {
+			PrivacyAction::Grant => "grant",
+			PrivacyAction::Revoke => "revoke",
+			PrivacyAction::Reset => "reset",
+		}
+	}
+}
+
+impl XcRunSimctlInstance<'_> {
+	fn privacy(
+		&self,
+		device_name: &DeviceName,
+		action: PrivacyAction,
+		service: PrivacyService,
+		bundle_id: Option<&str>,
+	) -> Result<PrivacyOutput> {
+		let mut command = self
+			.bossy_command()
+			.with_arg("privacy")
+			.with_arg(device_name.to_string())
+			.with_arg(action.as_arg())
+			.with_arg(service.as_arg());
+		if let Some(bundle_id) = bundle_id {
+			command.add_arg(bundle_id);
+		}
+		PrivacyOutput::from_bossy_result(command.run_and_wait_for_output())
+	}
+
+	/// Grants an app access to a service, without prompting the user
+	#[instrument(skip_all, ret, fields(%device_name, ?service, %bundle_id))]
+	pub fn privacy_grant(
+		&self,
+		device_name: &DeviceName,
+		service: PrivacyService,
+		bundle_id: &str,
+	) -> Result<PrivacyOutput> {
+		self.privacy(device_name, PrivacyAction::Grant, service, Some(bundle_id))
+	}
+
+	/// Revokes an app's access to a service
+	#[instrument(skip_all, ret, fields(%device_name, ?service, %bundle_id))]
+	pub fn privacy_revoke(
+		&self,
+		device_name: &DeviceName,
+		service: PrivacyService,
+		bundle_id: &str,
+	) -> Result<PrivacyOutput> {
+		self.privacy(device_name, PrivacyAction::Revoke, service, Some(bundle_id))
+	}
+
+	/// Resets access to a service, so the user will be prompted again.
+	/// If no bundle identifier is given, resets access for all apps.
+	#[instrument(skip_all, ret, fields(%device_name, ?service, ?bundle_id))]
+	pub fn privacy_reset(
+		&self,
+		device_name: &DeviceName,
+		service: PrivacyService,
+		bundle_id: Option<&str>,
+	) -> Result<PrivacyOutput> {
+		self.privacy(device_name, PrivacyAction::Reset, service, bundle_id)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn privacy_args() {
+		let xcrun = super::super::echo_xcrun();
+		let simctl = xcrun.simctl();
+		let device_name = DeviceName::from_str("iPhone 15 Pro").unwrap();
+		let stdout = |output: PrivacyOutput| match output {
+			PrivacyOutput::SuccessUnImplemented { stdout } => stdout,
+			output => panic!("Unexpected output {:?}", output),
+		};
+		assert_eq!(
+			stdout(
+				simctl
+					.privacy_grant(
+						&device_name,
+						PrivacyService::LocationAlways,
+						"com.example.app"
+					)
+					.unwrap()
+			),
+			"simctl privacy iPhone 15 Pro grant location-always com.example.app\n"
+		);
+		assert_eq!(
+			stdout(
+				simctl
+					.privacy_revoke(&device_name, PrivacyService::PhotosAdd, "com.example.app")
+					.unwrap()
+			),
+			"simctl privacy iPhone 15 Pro revoke photos-add com.example.app\n"
+		);
+		assert_eq!(
+			stdout(
+				simctl
+					.privacy_reset(
+						&device_name,
+						PrivacyService::Contacts,
+						Some("com.example.app")
+					)
+					.unwrap()
+			),
+			"simctl privacy iPhone 15 Pro reset contacts com.example.app\n"
+		);
+		assert_eq!(
+			stdout(
+				simctl
+					.privacy_reset(&device_name, PrivacyService::All, None)
+					.unwrap()
+			),
+			"simctl privacy iPhone 15 Pro reset all\n"
+		);
+	}
+}
@@ -0,0 +1,221 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+pub use payload::*;
+mod payload;
+
+#[cfg(feature = "cli")]
+#[derive(Args, Debug)]
+pub struct CliPushArgs {
+	/// A JSON file containing the APNs payload to send
+	#[clap(long, conflicts_with_all = ["title", "subtitle", "body", "badge", "sound", "category", "thread_id"])]
+	payload: Option<Utf8PathBuf>,
+
+	#[clap(long)]
+	title: Option<String>,
+
+	#[clap(long)]
+	subtitle: Option<String>,
+
+	#[clap(long)]
+	body: Option<String>,
+
+	#[clap(long)]
+	badge: Option<u32>,
+
+	/// E.g. "default"
+	#[clap(long)]
+	sound: Option<String>,
+
+	#[clap(long)]
+	category: Option<String>,
+
+	#[clap(long)]
+	thread_id: Option<String>,
+}
+
+/// What [CliPushArgs] resolves to
+#[cfg(feature = "cli")]
+#[derive(Debug)]
+pub enum CliPushPayload {
+	File(Utf8PathBuf),
+	Payload(PushPayload),
+}
+
+#[cfg(feature = "cli")]
+impl CliPushArgs {
+	pub fn resolve(self) -> color_eyre::Result<CliPushPayload> {
+		if let Some(path) = self.payload {
+			return Ok(CliPushPayload::File(path));
+		}
+		let mut payload = PushPayload::new();
+		if let Some(title) = self.title {
+			payload = payload.with_title(title);
+		}
+		if let Some(subtitle) = self.subtitle {
+			payload = payload.with_subtitle(subtitle);
+		}
+		if let Some(body) = self.body {
+			payload = payload.with_body(body);
+		}
+		if let Some(badge) = self.badge {
+			payload = payload.with_badge(badge);
+		}
+		if let Some(sound) = self.sound {
+			payload = payload.with_sound(sound);
+		}
+		if let Some(category) = self.category {
+			payload = payload.with_category(category);
+		}
+		if let Some(thread_id) = self.thread_id {
+			payload = payload.with_thread_id(thread_id);
+		}
+		if payload == PushPayload::default() {
+			Err(
+				eyre!("No push payload provided")
+					.with_suggestion(|| "try passing --payload <file>, or flags like --title and --body"),
+			)?
+		}
+		Ok(CliPushPayload::Payload(payload))
+	}
+}
+
+/// Returns [Error::PushPayloadTooLarge] if the file at `path` exceeds [PushPayload::MAX_SIZE]
+fn check_payload_file(path: &Utf8Path) -> Result<()> {
+	let metadata = std::fs::metadata(path).map_err(|err| Error::FileIo {
+		path: path.to_owned(),
+		err,
+	})?;
+	PushPayload::check_size(usize::try_from(metadata.len()).unwrap_or(usize::MAX))
+}
+
+impl XcRunSimctlInstance<'_> {
+	/// Sends a simulated push notification to an app, using a JSON payload file.
+	/// Returns [Error::PushPayloadTooLarge] without running `simctl` if the file
+	/// exceeds [PushPayload::MAX_SIZE]
+	#[instrument(skip_all, ret, fields(%device_name, %bundle_id))]
+	pub fn push_file(
+		&self,
+		device_name: &DeviceName,
+		bundle_id: &str,
+		payload_path: impl AsRef<Utf8Path>,
+	) -> Result<PushOutput> {
+		check_payload_file(payload_path.as_ref())?;
+		PushOutput::from_bossy_result(
+			self
+				.bossy_command()
+				.with_arg("push")
+				.with_arg(device_name.to_string())
+				.with_arg(bundle_id)
+				.with_arg(payload_path.as_ref())
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Sends a simulated push notification to an app.
+	/// The payload is validated and written to a temporary file first,
+	/// unique to each call so concurrent pushes don't overwrite each other.
+	#[instrument(skip_all, ret, fields(%device_name, %bundle_id))]
+	pub fn push(
+		&self,
+		device_name: &DeviceName,
+		bundle_id: &str,
+		payload: &PushPayload,
+	) -> Result<PushOutput> {
+		static PAYLOAD_FILES: AtomicUsize = AtomicUsize::new(0);
+		let json = payload.to_json()?;
+		let path = Utf8PathBuf::try_from(std::env::temp_dir())?.join(format!(
+			"apple-clis-push-{}-{}-{}.json",
+			std::process::id(),
+			PAYLOAD_FILES.fetch_add(1, Ordering::Relaxed),
+			bundle_id
+		));
+		std::fs::write(&path, json).map_err(|err| Error::FileIo {
+			path: path.clone(),
+			err,
+		})?;
+		let output = self.push_file(device_name, bundle_id, &path);
+		if let Err(err) = std::fs::remove_file(&path) {
+			warn!(?err, %path, "Couldn't remove temporary push payload file");
+		}
+		output
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn payload_file_too_large() {
+		let dir = Utf8PathBuf::try_from(std::env::temp_dir()).unwrap();
+		let path = dir.join(format!("apple-clis-push-test-{}.json", std::process::id()));
+
+		std::fs::write(&path, "a".repeat(PushPayload::MAX_SIZE)).unwrap();
+		assert!(check_payload_file(&path).is_ok());
+
+		std::fs::write(&path, "a".repeat(PushPayload::MAX_SIZE + 1)).unwrap();
+		let result = check_payload_file(&path);
+		std::fs::remove_file(&path).unwrap();
+		assert!(matches!(
+			result,
+			Err(Error::PushPayloadTooLarge { size, .. }) if size == PushPayload::MAX_SIZE + 1
+		));
+
+		assert!(matches!(
+			check_payload_file(&dir.join("apple-clis-missing-payload.json")),
+			Err(Error::FileIo { .. })
+		));
+	}
+
+	#[test]
+	fn push_args() {
+		let xcrun = super::super::echo_xcrun();
+		let simctl = xcrun.simctl();
+		let device_name = DeviceName::from_str("iPhone 15 Pro").unwrap();
+		let stdout = |output: PushOutput| match output {
+			PushOutput::SuccessUnImplemented { stdout } => stdout,
+			output => panic!("Unexpected output {:?}", output),
+		};
+
+		let dir = Utf8PathBuf::try_from(std::env::temp_dir()).unwrap();
+		let path = dir.join(format!("apple-clis-push-args-{}.json", std::process::id()));
+		std::fs::write(&path, r#"{"aps":{}}"#).unwrap();
+		let output = simctl.push_file(&device_name, "com.example.app", &path);
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(
+			stdout(output.unwrap()),
+			format!("simctl push iPhone 15 Pro com.example.app {}\n", path)
+		);
+
+		let payload = PushPayload::new().with_title("Hello");
+		let pushed_path = |output| {
+			let stdout = stdout(output);
+			let path = stdout
+				.strip_prefix("simctl push iPhone 15 Pro com.example.app ")
+				.unwrap()
+				.trim_end()
+				.to_owned();
+			// removed once `simctl` exits
+			assert!(!Utf8Path::new(&path).exists());
+			path
+		};
+		let first = pushed_path(
+			simctl
+				.push(&device_name, "com.example.app", &payload)
+				.unwrap(),
+		);
+		let second = pushed_path(
+			simctl
+				.push(&device_name, "com.example.app", &payload)
+				.unwrap(),
+		);
+		assert!(first.ends_with("-com.example.app.json"), "{}", first);
+		assert_ne!(first, second);
+	}
+}
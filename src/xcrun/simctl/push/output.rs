@@ -0,0 +1,65 @@
+use crate::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum PushOutput {
+	/// Notification sent to 'com.example.app'
+	Sent { bundle_id: String },
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for PushOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn success_nom_from_str(input: &str) -> IResult<&str, Self> {
+		let (remaining, bundle_id) = delimited(
+			ws(tag("Notification sent to '")),
+			take_till(|c| c == '\''),
+			ws(tag("'")),
+		)(input)?;
+		Ok((
+			remaining,
+			PushOutput::Sent {
+				bundle_id: bundle_id.to_owned(),
+			},
+		))
+	}
+}
+
+impl PublicCommandOutput for PushOutput {
+	type PrimarySuccess = ();
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			PushOutput::Sent { .. } | PushOutput::SuccessUnImplemented { .. } => Ok(&()),
+			PushOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_sent() {
+		let output = PushOutput::success_from_str("Notification sent to 'com.example.app'\n");
+		assert!(
+			matches!(output, PushOutput::Sent { ref bundle_id } if bundle_id == "com.example.app"),
+			"{:?}",
+			output
+		);
+	}
+}
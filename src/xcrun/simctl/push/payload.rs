@@ -0,0 +1,178 @@
+use crate::prelude::*;
+
+/// An APNs payload, as `simctl push` expects it.
+/// See <https://developer.apple.com/documentation/usernotifications/generating-a-remote-notification>
+///
+/// ```rust
+/// use apple_clis::xcrun::simctl::push::PushPayload;
+///
+/// let payload = PushPayload::new()
+///     .with_title("New message")
+///     .with_body("Hello from the simulator!")
+///     .with_badge(1)
+///     .with_sound("default")
+///     .with_custom("conversation_id", 42);
+/// let json = payload.to_json().unwrap();
+/// assert!(!json.contains("thread-id"));
+/// assert!(json.contains("\"conversation_id\":42"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PushPayload {
+	pub aps: Aps,
+
+	/// Custom keys, delivered to the app alongside the `aps` dictionary
+	#[serde(flatten)]
+	pub custom: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The `aps` dictionary of a [PushPayload]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Aps {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub alert: Option<Alert>,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub badge: Option<u32>,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub sound: Option<String>,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub category: Option<String>,
+
+	#[serde(rename = "thread-id", skip_serializing_if = "Option::is_none")]
+	pub thread_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub title: Option<String>,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub subtitle: Option<String>,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub body: Option<String>,
+}
+
+impl PushPayload {
+	/// APNs rejects regular remote notification payloads larger than this many bytes
+	pub const MAX_SIZE: usize = 4096;
+
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	fn alert_mut(&mut self) -> &mut Alert {
+		self.aps.alert.get_or_insert_with(Alert::default)
+	}
+
+	pub fn with_title(mut self, title: impl Into<String>) -> Self {
+		self.alert_mut().title = Some(title.into());
+		self
+	}
+
+	pub fn with_subtitle(mut self, subtitle: impl Into<String>) -> Self {
+		self.alert_mut().subtitle = Some(subtitle.into());
+		self
+	}
+
+	pub fn with_body(mut self, body: impl Into<String>) -> Self {
+		self.alert_mut().body = Some(body.into());
+		self
+	}
+
+	pub fn with_badge(mut self, badge: u32) -> Self {
+		self.aps.badge = Some(badge);
+		self
+	}
+
+	/// E.g. "default"
+	pub fn with_sound(mut self, sound: impl Into<String>) -> Self {
+		self.aps.sound = Some(sound.into());
+		self
+	}
+
+	pub fn with_category(mut self, category: impl Into<String>) -> Self {
+		self.aps.category = Some(category.into());
+		self
+	}
+
+	pub fn with_thread_id(mut self, thread_id: impl Into<String>) -> Self {
+		self.aps.thread_id = Some(thread_id.into());
+		self
+	}
+
+	/// Adds a custom key alongside the `aps` dictionary.
+	/// The key `aps` is reserved, and is ignored with a warning.
+	pub fn with_custom(
+		mut self,
+		key: impl Into<String>,
+		value: impl Into<serde_json::Value>,
+	) -> Self {
+		let key = key.into();
+		if key == "aps" {
+			warn!("Ignoring custom key `aps` in push payload, since it is reserved");
+		} else {
+			self.custom.insert(key, value.into());
+		}
+		self
+	}
+
+	/// Serializes the payload to JSON, returning [Error::PushPayloadTooLarge]
+	/// if it exceeds [PushPayload::MAX_SIZE]
+	pub fn to_json(&self) -> Result<String> {
+		let json = serde_json::to_string(self)?;
+		Self::check_size(json.len())?;
+		Ok(json)
+	}
+
+	/// Returns [Error::PushPayloadTooLarge] if `size` exceeds [PushPayload::MAX_SIZE]
+	pub(super) fn check_size(size: usize) -> Result<()> {
+		if size > Self::MAX_SIZE {
+			return Err(Error::PushPayloadTooLarge {
+				size,
+				max: Self::MAX_SIZE,
+			});
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn payload_serializes() {
+		let payload = PushPayload::new()
+			.with_title("Title")
+			.with_body("Body")
+			.with_badge(3)
+			.with_thread_id("thread")
+			.with_custom("aps", "ignored")
+			.with_custom("key", "value");
+		let json: serde_json::Value = serde_json::from_str(&payload.to_json().unwrap()).unwrap();
+		assert_eq!(
+			json,
+			serde_json::json!({
+				"aps": {
+					"alert": { "title": "Title", "body": "Body" },
+					"badge": 3,
+					"thread-id": "thread",
+				},
+				"key": "value",
+			})
+		);
+	}
+
+	#[test]
+	fn payload_too_large() {
+		let payload = PushPayload::new().with_body("a".repeat(PushPayload::MAX_SIZE));
+		assert!(matches!(
+			payload.to_json(),
+			Err(Error::PushPayloadTooLarge { .. })
+		));
+	}
+}
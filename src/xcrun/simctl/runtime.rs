@@ -0,0 +1,119 @@
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+
+impl XcRunSimctlInstance<'_> {
+	fn runtime_command(&self) -> bossy::Command {
+		self.bossy_command().with_arg("runtime")
+	}
+
+	/// Lists installed runtime images, including their disk usage
+	#[instrument(skip_all)]
+	pub fn runtime_list(&self) -> Result<RuntimeListOutput> {
+		RuntimeListOutput::from_bossy_result(
+			self
+				.runtime_command()
+				.with_arg("list")
+				.with_arg("--json")
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Installs a runtime from a disk image downloaded from the Apple developer site,
+	/// e.g. `iOS_17.4_Simulator_Runtime.dmg`
+	#[instrument(skip_all, ret, fields(image = %image.as_ref()))]
+	pub fn runtime_add(&self, image: impl AsRef<Utf8Path>) -> Result<RuntimeOutput> {
+		RuntimeOutput::from_bossy_result(
+			self
+				.runtime_command()
+				.with_arg("add")
+				.with_arg(image.as_ref())
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Deletes a runtime image by its [RuntimeImage::identifier].
+	/// See [RuntimeListJson::find] to find the image of a [crate::shared::identifiers::RuntimeIdentifier]
+	#[instrument(skip_all, ret, fields(image_identifier = image_identifier.as_ref()))]
+	pub fn runtime_delete(&self, image_identifier: impl AsRef<str>) -> Result<RuntimeOutput> {
+		RuntimeOutput::from_bossy_result(
+			self
+				.runtime_command()
+				.with_arg("delete")
+				.with_arg(image_identifier.as_ref())
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Deletes every deletable runtime image not used in the last `days` days.
+	/// With `dry_run`, only prints what would be deleted
+	#[instrument(skip_all, ret, fields(days, dry_run))]
+	pub fn runtime_delete_not_used_since(&self, days: u32, dry_run: bool) -> Result<RuntimeOutput> {
+		let mut command = self
+			.runtime_command()
+			.with_arg("delete")
+			.with_arg("all")
+			.with_arg("--notUsedSinceDays")
+			.with_arg(days.to_string());
+		if dry_run {
+			command.add_arg("--dry-run");
+		}
+		RuntimeOutput::from_bossy_result(command.run_and_wait_for_output())
+	}
+
+	/// Lists which runtime build each installed SDK will use
+	#[instrument(skip_all)]
+	pub fn runtime_match_list(&self) -> Result<RuntimeMatchListOutput> {
+		RuntimeMatchListOutput::from_bossy_result(
+			self
+				.runtime_command()
+				.with_arg("match")
+				.with_arg("list")
+				.with_arg("--json")
+				.run_and_wait_for_output(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn runtime_args() {
+		let xcrun = super::super::echo_xcrun();
+		let simctl = xcrun.simctl();
+		let stdout = |output: RuntimeOutput| match output {
+			RuntimeOutput::SuccessUnImplemented { stdout } => stdout,
+			output => panic!("Unexpected output {:?}", output),
+		};
+
+		assert_eq!(
+			stdout(
+				simctl
+					.runtime_add("iOS_17.4_Simulator_Runtime.dmg")
+					.unwrap()
+			),
+			"simctl runtime add iOS_17.4_Simulator_Runtime.dmg\n"
+		);
+		assert_eq!(
+			stdout(
+				simctl
+					.runtime_delete("8A5F7E3C-0000-4000-8000-000000000000")
+					.unwrap()
+			),
+			"simctl runtime delete 8A5F7E3C-0000-4000-8000-000000000000\n"
+		);
+		assert_eq!(
+			stdout(simctl.runtime_delete_not_used_since(30, false).unwrap()),
+			"simctl runtime delete all --notUsedSinceDays 30\n"
+		);
+		assert_eq!(
+			stdout(simctl.runtime_delete_not_used_since(30, true).unwrap()),
+			"simctl runtime delete all --notUsedSinceDays 30 --dry-run\n"
+		);
+	}
+}
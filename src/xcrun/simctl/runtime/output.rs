@@ -0,0 +1,290 @@
+use crate::prelude::*;
+use crate::shared::identifiers::RuntimeIdentifier;
+
+/// An installed runtime image, from `simctl runtime list --json`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct RuntimeImage {
+	/// A UUID, as passed to [crate::xcrun::simctl::XcRunSimctlInstance::runtime_delete]
+	pub identifier: String,
+	pub runtime_identifier: RuntimeIdentifier,
+	/// E.g. "17.4"
+	pub version: String,
+	/// E.g. "21E213"
+	pub build: String,
+	/// E.g. "Ready" or "Unusable"
+	pub state: String,
+	/// E.g. "Disk Image" or "Bundled with Xcode"
+	pub kind: String,
+	/// Runtimes bundled with Xcode can't be deleted with `simctl`
+	pub deletable: bool,
+	#[serde(default)]
+	pub size_bytes: u64,
+	pub path: Utf8PathBuf,
+	pub last_used_at: Option<String>,
+	pub unusable_error_message: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct RuntimeListJson {
+	images: HashMap<String, RuntimeImage>,
+}
+
+impl RuntimeListJson {
+	/// Images sorted by [RuntimeImage::runtime_identifier]
+	pub fn images(&self) -> Vec<&RuntimeImage> {
+		let mut images = self.images.values().collect::<Vec<_>>();
+		images.sort_by(|a, b| {
+			a.runtime_identifier
+				.as_str()
+				.cmp(b.runtime_identifier.as_str())
+				.then_with(|| a.build.cmp(&b.build))
+		});
+		images
+	}
+
+	/// Finds the image(s) of a runtime
+	pub fn find<'list>(
+		&'list self,
+		runtime: &'list RuntimeIdentifier,
+	) -> impl Iterator<Item = &'list RuntimeImage> + 'list {
+		self
+			.images
+			.values()
+			.filter(move |image| &image.runtime_identifier == runtime)
+	}
+
+	/// The disk space used by every image
+	pub fn total_size_bytes(&self) -> u64 {
+		self.images.values().map(|image| image.size_bytes).sum()
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum RuntimeListOutput {
+	SuccessJson(RuntimeListJson),
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented {
+		stdout: String,
+	},
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented {
+		stderr: String,
+	},
+}
+
+impl CommandNomParsable for RuntimeListOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn success_from_str(input: &str) -> Self {
+		match serde_json::from_str(input) {
+			Ok(output) => Self::SuccessJson(output),
+			Err(e) => {
+				error!(?e, "Failed to parse JSON");
+				Self::success_unimplemented(input.to_owned())
+			}
+		}
+	}
+}
+
+impl PublicCommandOutput for RuntimeListOutput {
+	type PrimarySuccess = RuntimeListJson;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			RuntimeListOutput::SuccessJson(output) => Ok(output),
+			_ => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+/// Output of `simctl runtime add` and `simctl runtime delete`
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum RuntimeOutput {
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	/// Adding or deleting the runtime image needs more permissions, e.g.
+	/// ```text
+	/// An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=1):
+	/// Operation not permitted
+	/// ```
+	ErrorPermissionDenied { error: NSError },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for RuntimeOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn errored_nom_from_str(input: &str) -> IResult<&str, Self> {
+		map_opt(NSError::nom_from_str, |error| {
+			// EPERM, EACCES and NSFileWriteNoPermissionError
+			let permission_denied = error.any_is("NSPOSIXErrorDomain", 1)
+				|| error.any_is("NSPOSIXErrorDomain", 13)
+				|| error.any_is("NSCocoaErrorDomain", 513);
+			if !permission_denied {
+				return None;
+			}
+			let output = Self::ErrorPermissionDenied { error };
+			warn!(?output, "Parsed xcrun simctl runtime error");
+			Some(output)
+		})(input)
+	}
+}
+
+impl PublicCommandOutput for RuntimeOutput {
+	type PrimarySuccess = ();
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			RuntimeOutput::SuccessUnImplemented { .. } => Ok(&()),
+			RuntimeOutput::ErrorPermissionDenied { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Managing runtimes may need `sudo`",
+			)),
+			RuntimeOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+/// Which runtime an SDK will use, from `simctl runtime match list --json`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct RuntimeMatch {
+	/// E.g. "com.apple.platform.iphoneos"
+	pub platform: String,
+	/// E.g. "17.5"
+	pub sdk_version: String,
+	pub sdk_build: String,
+	/// [None] if no installed runtime matches the SDK
+	pub chosen_runtime_build: Option<String>,
+	pub preferred_build: Option<String>,
+	pub default_build: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct RuntimeMatchListJson {
+	/// Keyed by SDK, e.g. "iphoneos17.5"
+	pub matches: HashMap<String, RuntimeMatch>,
+}
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum RuntimeMatchListOutput {
+	SuccessJson(RuntimeMatchListJson),
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented {
+		stdout: String,
+	},
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented {
+		stderr: String,
+	},
+}
+
+impl CommandNomParsable for RuntimeMatchListOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn success_from_str(input: &str) -> Self {
+		match serde_json::from_str(input) {
+			Ok(output) => Self::SuccessJson(output),
+			Err(e) => {
+				error!(?e, "Failed to parse JSON");
+				Self::success_unimplemented(input.to_owned())
+			}
+		}
+	}
+}
+
+impl PublicCommandOutput for RuntimeMatchListOutput {
+	type PrimarySuccess = RuntimeMatchListJson;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			RuntimeMatchListOutput::SuccessJson(output) => Ok(output),
+			_ => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_runtime_list() {
+		let output = RuntimeListOutput::success_from_str(include_str!(concat!(
+			env!("CARGO_MANIFEST_DIR"),
+			"/tests/simctl-runtime-list.json"
+		)));
+		let list = output.success().unwrap();
+		assert_eq!(list.images().len(), 3);
+		assert_eq!(list.total_size_bytes(), 7165411328 + 7310528512);
+		let runtime: RuntimeIdentifier =
+			serde_json::from_str("\"com.apple.CoreSimulator.SimRuntime.iOS-16-4\"").unwrap();
+		let image = list.find(&runtime).next().unwrap();
+		assert!(!image.deletable);
+		assert!(image.unusable_error_message.is_some());
+	}
+
+	#[test]
+	fn parses_runtime_match_list() {
+		let output = RuntimeMatchListOutput::success_from_str(include_str!(concat!(
+			env!("CARGO_MANIFEST_DIR"),
+			"/tests/simctl-runtime-match-list.json"
+		)));
+		let list = output.success().unwrap();
+		assert_eq!(
+			list.matches["iphoneos17.5"].chosen_runtime_build.as_deref(),
+			Some("21F79")
+		);
+		assert_eq!(list.matches["watchos10.5"].chosen_runtime_build, None);
+	}
+
+	#[test]
+	fn runtime_output_hints() {
+		let hint = |stderr: &str| match RuntimeOutput::errored_from_str(stderr).success() {
+			Err(Error::OutputErrored { help_hint, .. }) => help_hint,
+			result => panic!("Expected an error, got {:?}", result),
+		};
+		assert!(hint("An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=1):\nOperation not permitted\n")
+			.unwrap()
+			.contains("sudo"));
+		assert_eq!(
+			hint("An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=404):\nNo such runtime\n"),
+			None
+		);
+		assert_eq!(hint("Usage: simctl runtime delete\n"), None);
+	}
+}
@@ -0,0 +1,57 @@
+use crate::{prelude::*, shared::identifiers::DeviceName};
+
+use super::XcRunSimctlInstance;
+
+pub use self::output::ShutdownOutput;
+mod output;
+
+impl XcRunSimctlInstance<'_> {
+	/// This will not fail if the device is already shutdown,
+	/// but will return [ShutdownOutput::AlreadyShutdown] in that case.
+	#[tracing::instrument(skip_all, ret)]
+	pub fn shutdown(&self, device_name: impl Into<DeviceName>) -> Result<ShutdownOutput> {
+		ShutdownOutput::from_bossy_result(
+			self
+				.bossy_command()
+				.with_arg("shutdown")
+				.with_arg(device_name.into().to_string())
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Shuts down every booted simulator
+	#[tracing::instrument(skip_all, ret)]
+	pub fn shutdown_all(&self) -> Result<ShutdownOutput> {
+		ShutdownOutput::from_bossy_result(
+			self
+				.bossy_command()
+				.with_arg("shutdown")
+				.with_arg("all")
+				.run_and_wait_for_output(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn shutdown_args() {
+		let xcrun = super::super::echo_xcrun();
+		let simctl = xcrun.simctl();
+		let stdout = |output: ShutdownOutput| match output {
+			ShutdownOutput::SuccessUnImplemented { stdout } => stdout,
+			output => panic!("Unexpected output {:?}", output),
+		};
+		let device_name = DeviceName::from_str("iPhone 15 Pro").unwrap();
+		assert_eq!(
+			stdout(simctl.shutdown(device_name).unwrap()),
+			"simctl shutdown iPhone 15 Pro\n"
+		);
+		assert_eq!(
+			stdout(simctl.shutdown_all().unwrap()),
+			"simctl shutdown all\n"
+		);
+	}
+}
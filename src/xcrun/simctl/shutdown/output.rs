@@ -0,0 +1,76 @@
+use crate::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum ShutdownOutput {
+	/// NOT considered an error case, since the simulator is *already* shutdown.
+	/// ```text
+	/// An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):
+	/// Unable to shutdown device in current state: Shutdown
+	/// ```
+	/// Other states, e.g. `Booting`, are errors
+	AlreadyShutdown,
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for ShutdownOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn errored_nom_from_str(input: &str) -> IResult<&str, Self> {
+		map_opt(NSError::nom_from_str, |error| {
+			(error.invalid_state() == Some("Shutdown")).then_some(ShutdownOutput::AlreadyShutdown)
+		})(input)
+	}
+}
+
+impl PublicCommandOutput for ShutdownOutput {
+	/// If successful, the simulator is shutdown
+	type PrimarySuccess = ();
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			ShutdownOutput::SuccessUnImplemented { .. } | ShutdownOutput::AlreadyShutdown => Ok(&()),
+			ShutdownOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_errors() {
+		let invalid_state = |state: &str| {
+			format!("An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):\nUnable to shutdown device in current state: {state}\n")
+		};
+
+		let output = ShutdownOutput::errored_from_str(&invalid_state("Shutdown"));
+		assert!(
+			matches!(output, ShutdownOutput::AlreadyShutdown),
+			"{:?}",
+			output
+		);
+
+		for state in ["Booting", "Creating"] {
+			let output = ShutdownOutput::errored_from_str(&invalid_state(state));
+			assert!(
+				matches!(output, ShutdownOutput::ErrorUnImplemented { .. }),
+				"{:?}",
+				output
+			);
+		}
+	}
+}
@@ -0,0 +1,137 @@
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+
+/// `simctl spawn` forwards environment variables with this prefix to the spawned process,
+/// with the prefix removed
+const CHILD_ENV_PREFIX: &str = "SIMCTL_CHILD_";
+
+/// An executable to run inside a simulator with `simctl spawn`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpawnCommand {
+	/// A path inside the simulator's runtime, e.g. `/usr/bin/log`,
+	/// a path on the host built for the simulator, or a name looked up on the simulator's `PATH`
+	pub executable: String,
+	pub args: Vec<String>,
+	/// Set in the spawned process's environment
+	pub env: Vec<(String, String)>,
+}
+
+impl SpawnCommand {
+	pub fn new(executable: impl Into<String>) -> Self {
+		Self {
+			executable: executable.into(),
+			args: Vec::new(),
+			env: Vec::new(),
+		}
+	}
+
+	pub fn with_arg(mut self, arg: impl Into<String>) -> Self {
+		self.args.push(arg.into());
+		self
+	}
+
+	pub fn with_args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+		self.args.extend(args.into_iter().map(Into::into));
+		self
+	}
+
+	pub fn with_env_var(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+		self.env.push((key.into(), value.into()));
+		self
+	}
+
+	pub(super) fn simctl_args(&self, device_name: &DeviceName) -> Vec<String> {
+		let mut args = vec![
+			"spawn".into(),
+			device_name.to_string(),
+			self.executable.clone(),
+		];
+		args.extend(self.args.iter().cloned());
+		args
+	}
+
+	fn simctl_env(&self) -> impl Iterator<Item = (String, &str)> {
+		self
+			.env
+			.iter()
+			.map(|(key, value)| (format!("{}{}", CHILD_ENV_PREFIX, key), value.as_str()))
+	}
+}
+
+impl XcRunSimctlInstance<'_> {
+	pub(super) fn spawn_bossy_command(
+		&self,
+		device_name: &DeviceName,
+		command: &SpawnCommand,
+	) -> bossy::Command {
+		let mut bossy = self
+			.bossy_command()
+			.with_args(command.simctl_args(device_name));
+		for (key, value) in command.simctl_env() {
+			bossy.add_env_var(key, value);
+		}
+		bossy
+	}
+
+	/// For long running processes, see [XcRunSimctlInstance::std_command]
+	pub(super) fn spawn_std_command(
+		&self,
+		device_name: &DeviceName,
+		command: &SpawnCommand,
+	) -> std::process::Command {
+		let mut std = self.std_command();
+		std
+			.args(command.simctl_args(device_name))
+			.envs(command.simctl_env());
+		std
+	}
+
+	/// Runs an executable inside a booted simulator, waiting for it to exit
+	/// and capturing its output
+	#[instrument(skip_all, ret, fields(%device_name, ?command))]
+	pub fn spawn(&self, device_name: &DeviceName, command: &SpawnCommand) -> Result<SpawnOutput> {
+		SpawnOutput::from_bossy_output_result(
+			self
+				.spawn_bossy_command(device_name, command)
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Reads an environment variable of a booted simulator
+	#[instrument(skip_all, ret, fields(%device_name, name = name.as_ref()))]
+	pub fn getenv(&self, device_name: &DeviceName, name: impl AsRef<str>) -> Result<GetenvOutput> {
+		GetenvOutput::from_bossy_result(
+			self
+				.bossy_command()
+				.with_arg("getenv")
+				.with_arg(device_name.to_string())
+				.with_arg(name.as_ref())
+				.run_and_wait_for_output(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn spawn_args_and_env() {
+		let command = SpawnCommand::new("/usr/bin/env")
+			.with_arg("-0")
+			.with_env_var("RUST_LOG", "debug");
+		let device_name = DeviceName::from_str("iPhone 15").unwrap();
+		assert_eq!(
+			command.simctl_args(&device_name),
+			["spawn", "iPhone 15", "/usr/bin/env", "-0"]
+		);
+		assert_eq!(
+			command.simctl_env().collect::<Vec<_>>(),
+			[("SIMCTL_CHILD_RUST_LOG".to_owned(), "debug")]
+		);
+	}
+}
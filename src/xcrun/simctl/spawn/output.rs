@@ -0,0 +1,187 @@
+use crate::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum SpawnOutput {
+	/// The spawned process ran and exited, though not necessarily successfully
+	Exited {
+		/// [None] if the process was terminated by a signal
+		code: Option<i32>,
+		stdout: String,
+		stderr: String,
+	},
+
+	/// `simctl` itself failed, e.g. because the device isn't booted
+	/// or the executable doesn't exist:
+	/// ```text
+	/// An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=2):
+	/// The operation couldn’t be completed. No such file or directory
+	/// ```
+	ErrorSimctl { error: NSError },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for SpawnOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::Exited {
+			code: Some(0),
+			stdout,
+			stderr: String::new(),
+		}
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+}
+
+impl SpawnOutput {
+	/// Unlike [CommandNomParsable::from_bossy_result], keeps both streams
+	/// since the spawned process may write to either
+	pub(super) fn from_bossy_output_result(result: bossy::Result<bossy::Output>) -> Result<Self> {
+		let (code, stdout, stderr) = match &result {
+			Ok(output) => (output.status().code(), output.stdout(), output.stderr()),
+			Err(err) => match err.output() {
+				Some(output) => (output.status().code(), output.stdout(), output.stderr()),
+				None => Err(Error::CannotLocateStderrStream {
+					err: result.unwrap_err(),
+				})?,
+			},
+		};
+		let stdout = String::from_utf8_lossy(stdout).into_owned();
+		let stderr = String::from_utf8_lossy(stderr).into_owned();
+		if code != Some(0) && stdout.is_empty() {
+			if let Ok(error) = NSError::from_str(stderr.trim()) {
+				return Ok(SpawnOutput::ErrorSimctl { error });
+			}
+		}
+		Ok(SpawnOutput::Exited {
+			code,
+			stdout,
+			stderr,
+		})
+	}
+
+	pub fn stdout(&self) -> Option<&str> {
+		match self {
+			SpawnOutput::Exited { stdout, .. } => Some(stdout),
+			_ => None,
+		}
+	}
+
+	pub fn stderr(&self) -> Option<&str> {
+		match self {
+			SpawnOutput::Exited { stderr, .. } => Some(stderr),
+			_ => None,
+		}
+	}
+}
+
+impl PublicCommandOutput for SpawnOutput {
+	/// The exit code
+	type PrimarySuccess = i32;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			SpawnOutput::Exited { code: Some(0), .. } => Ok(&0),
+			SpawnOutput::Exited { .. } | SpawnOutput::ErrorUnImplemented { .. } => {
+				Err(Error::output_errored(self))
+			}
+			SpawnOutput::ErrorSimctl { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"Check the simulator is booted and the executable exists and is built for the simulator",
+			)),
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum GetenvOutput {
+	/// The value of the variable, without the trailing newline
+	Value { value: String },
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for GetenvOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn success_from_str(input: &str) -> Self {
+		Self::Value {
+			value: input.strip_suffix('\n').unwrap_or(input).to_owned(),
+		}
+	}
+}
+
+impl PublicCommandOutput for GetenvOutput {
+	type PrimarySuccess = String;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			GetenvOutput::Value { value } => Ok(value),
+			_ => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn spawn_sh(script: &str) -> SpawnOutput {
+		SpawnOutput::from_bossy_output_result(
+			bossy::Command::pure("/bin/sh")
+				.with_args(["-c", script])
+				.run_and_wait_for_output(),
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn classifies_spawn_output() {
+		let output = spawn_sh(
+			"echo 'An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=2):' >&2; \
+			echo 'No such file or directory' >&2; exit 1",
+		);
+		match &output {
+			SpawnOutput::ErrorSimctl { error } => assert!(error.is("NSPOSIXErrorDomain", 2)),
+			output => panic!("Unexpected output {:?}", output),
+		}
+		assert!(output.failed());
+
+		// the app's own failures keep both streams
+		let output = spawn_sh("echo out; echo err >&2; exit 3");
+		assert!(matches!(
+			output,
+			SpawnOutput::Exited { code: Some(3), ref stdout, ref stderr } if stdout == "out\n" && stderr == "err\n"
+		));
+		assert!(output.failed());
+		let output = spawn_sh("echo 'not an NSError' >&2; exit 2");
+		assert!(matches!(output, SpawnOutput::Exited { code: Some(2), .. }));
+
+		let output = spawn_sh("echo ok");
+		assert_eq!(output.success().unwrap(), &0);
+		assert_eq!(output.stdout(), Some("ok\n"));
+	}
+
+	#[test]
+	fn getenv_value() {
+		let output = GetenvOutput::success_from_str("/Users/example\n");
+		assert_eq!(output.success().unwrap(), "/Users/example");
+	}
+}
@@ -0,0 +1,315 @@
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum DataNetwork {
+	Hide,
+	Wifi,
+	#[cfg_attr(feature = "cli", value(name = "3g"))]
+	ThreeG,
+	#[cfg_attr(feature = "cli", value(name = "4g"))]
+	FourG,
+	Lte,
+	#[cfg_attr(feature = "cli", value(name = "lte-a"))]
+	LteA,
+	#[cfg_attr(feature = "cli", value(name = "lte+"))]
+	LtePlus,
+	#[cfg_attr(feature = "cli", value(name = "5g"))]
+	FiveG,
+	#[cfg_attr(feature = "cli", value(name = "5g+"))]
+	FiveGPlus,
+	#[cfg_attr(feature = "cli", value(name = "5g-uwb"))]
+	FiveGUwb,
+	#[cfg_attr(feature = "cli", value(name = "5g-uc"))]
+	FiveGUc,
+}
+
+impl DataNetwork {
+	fn as_arg(&self) -> &'static str {
+		match self {
+			DataNetwork::Hide => "hide",
+			DataNetwork::Wifi => "wifi",
+			DataNetwork::ThreeG => "3g",
+			DataNetwork::FourG => "4g",
+			DataNetwork::Lte => "lte",
+			DataNetwork::LteA => "lte-a",
+			DataNetwork::LtePlus => "lte+",
+			DataNetwork::FiveG => "5g",
+			DataNetwork::FiveGPlus => "5g+",
+			DataNetwork::FiveGUwb => "5g-uwb",
+			DataNetwork::FiveGUc => "5g-uc",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum WifiMode {
+	Searching,
+	Failed,
+	Active,
+}
+
+impl WifiMode {
+	fn as_arg(&self) -> &'static str {
+		match self {
+			WifiMode::Searching => "searching",
+			WifiMode::Failed => "failed",
+			WifiMode::Active => "active",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum CellularMode {
+	NotSupported,
+	Searching,
+	Failed,
+	Active,
+}
+
+impl CellularMode {
+	fn as_arg(&self) -> &'static str {
+		match self {
+			CellularMode::NotSupported => "notSupported",
+			CellularMode::Searching => "searching",
+			CellularMode::Failed => "failed",
+			CellularMode::Active => "active",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum BatteryState {
+	Charging,
+	Charged,
+	Discharging,
+}
+
+impl BatteryState {
+	fn as_arg(&self) -> &'static str {
+		match self {
+			BatteryState::Charging => "charging",
+			BatteryState::Charged => "charged",
+			BatteryState::Discharging => "discharging",
+		}
+	}
+}
+
+/// Overrides for `simctl status_bar override`.
+/// Fields left as [None] are not overridden.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::Args))]
+pub struct StatusBarOverrides {
+	/// Any string, e.g. "9:41"
+	#[cfg_attr(feature = "cli", arg(long))]
+	pub time: Option<String>,
+
+	#[cfg_attr(feature = "cli", arg(long, value_enum))]
+	pub data_network: Option<DataNetwork>,
+
+	#[cfg_attr(feature = "cli", arg(long, value_enum))]
+	pub wifi_mode: Option<WifiMode>,
+
+	/// 0 to 3
+	#[cfg_attr(feature = "cli", arg(long))]
+	pub wifi_bars: Option<u8>,
+
+	#[cfg_attr(feature = "cli", arg(long, value_enum))]
+	pub cellular_mode: Option<CellularMode>,
+
+	/// 0 to 4
+	#[cfg_attr(feature = "cli", arg(long))]
+	pub cellular_bars: Option<u8>,
+
+	/// An empty string hides the operator name
+	#[cfg_attr(feature = "cli", arg(long))]
+	pub operator_name: Option<String>,
+
+	#[cfg_attr(feature = "cli", arg(long, value_enum))]
+	pub battery_state: Option<BatteryState>,
+
+	/// 0 to 100
+	#[cfg_attr(feature = "cli", arg(long))]
+	pub battery_level: Option<u8>,
+}
+
+impl StatusBarOverrides {
+	pub const MAX_WIFI_BARS: u8 = 3;
+	pub const MAX_CELLULAR_BARS: u8 = 4;
+	pub const MAX_BATTERY_LEVEL: u8 = 100;
+
+	/// The classic App Store screenshot look:
+	/// 9:41, full Wi-Fi and cellular bars, a full battery and no operator name
+	pub fn marketing() -> Self {
+		Self {
+			time: Some("9:41".into()),
+			data_network: Some(DataNetwork::Wifi),
+			wifi_mode: Some(WifiMode::Active),
+			wifi_bars: Some(Self::MAX_WIFI_BARS),
+			cellular_mode: Some(CellularMode::Active),
+			cellular_bars: Some(Self::MAX_CELLULAR_BARS),
+			operator_name: Some("".into()),
+			battery_state: Some(BatteryState::Charged),
+			battery_level: Some(Self::MAX_BATTERY_LEVEL),
+		}
+	}
+
+	/// Uses the fields of `self` where set, otherwise those of `base`
+	pub fn or(self, base: Self) -> Self {
+		Self {
+			time: self.time.or(base.time),
+			data_network: self.data_network.or(base.data_network),
+			wifi_mode: self.wifi_mode.or(base.wifi_mode),
+			wifi_bars: self.wifi_bars.or(base.wifi_bars),
+			cellular_mode: self.cellular_mode.or(base.cellular_mode),
+			cellular_bars: self.cellular_bars.or(base.cellular_bars),
+			operator_name: self.operator_name.or(base.operator_name),
+			battery_state: self.battery_state.or(base.battery_state),
+			battery_level: self.battery_level.or(base.battery_level),
+		}
+	}
+
+	fn validate(name: &'static str, value: Option<u8>, max: u8) -> Result<()> {
+		match value {
+			Some(value) if value > max => Err(Error::StatusBarOverrideOutOfRange { name, value, max }),
+			_ => Ok(()),
+		}
+	}
+
+	/// The arguments passed to `simctl status_bar override`
+	fn args(&self) -> Result<Vec<String>> {
+		Self::validate("wifi_bars", self.wifi_bars, Self::MAX_WIFI_BARS)?;
+		Self::validate("cellular_bars", self.cellular_bars, Self::MAX_CELLULAR_BARS)?;
+		Self::validate("battery_level", self.battery_level, Self::MAX_BATTERY_LEVEL)?;
+
+		let mut args = Vec::new();
+		let mut push = |flag: &str, value: Option<String>| {
+			if let Some(value) = value {
+				args.push(flag.to_owned());
+				args.push(value);
+			}
+		};
+		push("--time", self.time.clone());
+		push(
+			"--dataNetwork",
+			self.data_network.map(|v| v.as_arg().to_owned()),
+		);
+		push("--wifiMode", self.wifi_mode.map(|v| v.as_arg().to_owned()));
+		push("--wifiBars", self.wifi_bars.map(|v| v.to_string()));
+		push(
+			"--cellularMode",
+			self.cellular_mode.map(|v| v.as_arg().to_owned()),
+		);
+		push("--cellularBars", self.cellular_bars.map(|v| v.to_string()));
+		push("--operatorName", self.operator_name.clone());
+		push(
+			"--batteryState",
+			self.battery_state.map(|v| v.as_arg().to_owned()),
+		);
+		push("--batteryLevel", self.battery_level.map(|v| v.to_string()));
+		Ok(args)
+	}
+}
+
+impl XcRunSimctlInstance<'_> {
+	fn status_bar_command(&self, device_name: &DeviceName) -> bossy::Command {
+		self
+			.bossy_command()
+			.with_arg("status_bar")
+			.with_arg(device_name.to_string())
+	}
+
+	/// Overrides the status bar, see [StatusBarOverrides::marketing] for a common preset
+	#[instrument(skip_all, ret, fields(%device_name, ?overrides))]
+	pub fn status_bar_override(
+		&self,
+		device_name: &DeviceName,
+		overrides: &StatusBarOverrides,
+	) -> Result<StatusBarOutput> {
+		StatusBarOutput::from_bossy_result(
+			self
+				.status_bar_command(device_name)
+				.with_arg("override")
+				.with_args(overrides.args()?)
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Clears all status bar overrides
+	#[instrument(skip_all, ret, fields(%device_name))]
+	pub fn status_bar_clear(&self, device_name: &DeviceName) -> Result<StatusBarOutput> {
+		StatusBarOutput::from_bossy_result(
+			self
+				.status_bar_command(device_name)
+				.with_arg("clear")
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Lists the current status bar overrides
+	#[instrument(skip_all, ret, fields(%device_name))]
+	pub fn status_bar_list(&self, device_name: &DeviceName) -> Result<StatusBarListOutput> {
+		StatusBarListOutput::from_bossy_result(
+			self
+				.status_bar_command(device_name)
+				.with_arg("list")
+				.run_and_wait_for_output(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn marketing_args() {
+		let args = StatusBarOverrides::marketing().args().unwrap();
+		assert_eq!(
+			args,
+			[
+				"--time",
+				"9:41",
+				"--dataNetwork",
+				"wifi",
+				"--wifiMode",
+				"active",
+				"--wifiBars",
+				"3",
+				"--cellularMode",
+				"active",
+				"--cellularBars",
+				"4",
+				"--operatorName",
+				"",
+				"--batteryState",
+				"charged",
+				"--batteryLevel",
+				"100",
+			]
+		);
+	}
+
+	#[test]
+	fn overrides_validated() {
+		let overrides = StatusBarOverrides {
+			wifi_bars: Some(4),
+			..Default::default()
+		};
+		assert!(matches!(
+			overrides.args(),
+			Err(Error::StatusBarOverrideOutOfRange {
+				name: "wifi_bars",
+				..
+			})
+		));
+	}
+}
@@ -0,0 +1,203 @@
+use crate::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum StatusBarOutput {
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+impl CommandNomParsable for StatusBarOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+}
+
+impl PublicCommandOutput for StatusBarOutput {
+	type PrimarySuccess = ();
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			StatusBarOutput::SuccessUnImplemented { .. } => Ok(&()),
+			StatusBarOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+/// One override printed by `simctl status_bar list`, as displayed,
+/// e.g. `Time` is `9:41` and `WiFi Bars` is `3`.
+/// Modes and states are printed as `simctl`'s internal numbers, so are left as strings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatusBarListed {
+	pub name: String,
+	pub value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum StatusBarListOutput {
+	/// The current overrides, empty if nothing is overridden
+	Overrides { overrides: Vec<StatusBarListed> },
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented { stdout: String },
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented { stderr: String },
+}
+
+/// The names `simctl status_bar list` prints, which separate the overrides sharing a line
+const LISTED_NAMES: &[&str] = &[
+	"Time",
+	"DataNetworkType",
+	"WiFi Mode",
+	"WiFi Bars",
+	"Cell Mode",
+	"Cell Bars",
+	"Operator Name",
+	"Battery State",
+	"Battery Level",
+	"Show Not Charging",
+];
+
+/// Splits a line like `WiFi Mode: 3, WiFi Bars: 3` into its overrides.
+/// Values can contain ", ", e.g. an operator name, so a line is only split
+/// before a known name
+fn parse_listed_line(line: &str) -> Option<Vec<StatusBarListed>> {
+	let mut overrides = Vec::new();
+	let mut rest = line;
+	while !rest.is_empty() {
+		let (name, after_name) = rest.split_once(": ").or_else(|| {
+			// an empty value at the end of the line, e.g. `Operator Name: `
+			rest.strip_suffix(':').map(|name| (name, ""))
+		})?;
+		let value_end = LISTED_NAMES
+			.iter()
+			.filter_map(|next| after_name.find(&format!(", {}: ", next)))
+			.min();
+		let (value, after_value) = match value_end {
+			Some(end) => (&after_name[..end], &after_name[end + ", ".len()..]),
+			None => (after_name, ""),
+		};
+		overrides.push(StatusBarListed {
+			name: name.trim().to_owned(),
+			value: value.trim().to_owned(),
+		});
+		rest = after_value;
+	}
+	Some(overrides)
+}
+
+impl StatusBarListOutput {
+	/// The value of the override displayed as `name`, e.g. "Battery Level"
+	pub fn get(&self, name: &str) -> Option<&str> {
+		match self {
+			StatusBarListOutput::Overrides { overrides } => overrides
+				.iter()
+				.find(|listed| listed.name == name)
+				.map(|listed| listed.value.as_str()),
+			_ => None,
+		}
+	}
+}
+
+impl CommandNomParsable for StatusBarListOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	/// Parses lines like `WiFi Mode: 3, WiFi Bars: 3` after the
+	/// `Current Status Bar Overrides:` header
+	fn success_from_str(input: &str) -> Self {
+		let mut overrides = Vec::new();
+		for line in input.lines() {
+			if line.starts_with("Current Status Bar Overrides")
+				|| line.starts_with('=')
+				|| line.trim().is_empty()
+			{
+				continue;
+			}
+			match parse_listed_line(line) {
+				Some(listed) => overrides.extend(listed),
+				None => {
+					error!(%line, "Failed to parse status bar list output");
+					return Self::success_unimplemented(input.to_owned());
+				}
+			}
+		}
+		Self::Overrides { overrides }
+	}
+}
+
+impl PublicCommandOutput for StatusBarListOutput {
+	type PrimarySuccess = Vec<StatusBarListed>;
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			StatusBarListOutput::Overrides { overrides } => Ok(overrides),
+			_ => Err(Error::output_errored(self)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_list() {
+		let stdout = "Current Status Bar Overrides:\n\
+			=============================\n\
+			Time: 9:41\n\
+			DataNetworkType: 1\n\
+			WiFi Mode: 3, WiFi Bars: 3\n\
+			Cell Mode: 3, Cell Bars: 4\n\
+			Operator Name: \n\
+			Battery State: 2, Battery Level: 100, Show Not Charging: 0\n";
+		let output = StatusBarListOutput::success_from_str(stdout);
+		assert_eq!(output.success().unwrap().len(), 10);
+		assert_eq!(output.get("Time"), Some("9:41"));
+		assert_eq!(output.get("WiFi Bars"), Some("3"));
+		assert_eq!(output.get("Operator Name"), Some(""));
+		assert_eq!(output.get("Battery Level"), Some("100"));
+		assert_eq!(output.get("Missing"), None);
+
+		let output = StatusBarListOutput::success_from_str("");
+		assert!(output.success().unwrap().is_empty());
+	}
+
+	#[test]
+	fn keeps_separators_in_values() {
+		let stdout = "Current Status Bar Overrides:\n\
+			=============================\n\
+			Operator Name: Carrier, Inc., Cell Mode: 3, Cell Bars: 4\n";
+		let output = StatusBarListOutput::success_from_str(stdout);
+		assert_eq!(output.success().unwrap().len(), 3);
+		assert_eq!(output.get("Operator Name"), Some("Carrier, Inc."));
+		assert_eq!(output.get("Cell Mode"), Some("3"));
+		assert_eq!(output.get("Cell Bars"), Some("4"));
+	}
+
+	#[test]
+	fn falls_back_on_unexpected_output() {
+		let output = StatusBarListOutput::success_from_str("No overrides are set up here\n");
+		assert!(matches!(
+			output,
+			StatusBarListOutput::SuccessUnImplemented { .. }
+		));
+		assert!(output.success().is_err());
+	}
+}
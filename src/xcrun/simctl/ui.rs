@@ -0,0 +1,238 @@
+use crate::prelude::*;
+
+use super::XcRunSimctlInstance;
+
+pub use output::*;
+mod output;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum UiAppearance {
+	Light,
+	Dark,
+}
+
+impl UiAppearance {
+	const ALL: [Self; 2] = [Self::Light, Self::Dark];
+
+	fn as_arg(&self) -> &'static str {
+		match self {
+			UiAppearance::Light => "light",
+			UiAppearance::Dark => "dark",
+		}
+	}
+}
+
+/// The Dynamic Type size, from the smallest to the largest accessibility size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ContentSize {
+	ExtraSmall,
+	Small,
+	Medium,
+	/// The default size
+	Large,
+	ExtraLarge,
+	ExtraExtraLarge,
+	ExtraExtraExtraLarge,
+	AccessibilityMedium,
+	AccessibilityLarge,
+	AccessibilityExtraLarge,
+	AccessibilityExtraExtraLarge,
+	AccessibilityExtraExtraExtraLarge,
+}
+
+impl ContentSize {
+	const ALL: [Self; 12] = [
+		Self::ExtraSmall,
+		Self::Small,
+		Self::Medium,
+		Self::Large,
+		Self::ExtraLarge,
+		Self::ExtraExtraLarge,
+		Self::ExtraExtraExtraLarge,
+		Self::AccessibilityMedium,
+		Self::AccessibilityLarge,
+		Self::AccessibilityExtraLarge,
+		Self::AccessibilityExtraExtraLarge,
+		Self::AccessibilityExtraExtraExtraLarge,
+	];
+
+	fn as_arg(&self) -> &'static str {
+		match self {
+			ContentSize::ExtraSmall => "extra-small",
+			ContentSize::Small => "small",
+			ContentSize::Medium => "medium",
+			ContentSize::Large => "large",
+			ContentSize::ExtraLarge => "extra-large",
+			ContentSize::ExtraExtraLarge => "extra-extra-large",
+			ContentSize::ExtraExtraExtraLarge => "extra-extra-extra-large",
+			ContentSize::AccessibilityMedium => "accessibility-medium",
+			ContentSize::AccessibilityLarge => "accessibility-large",
+			ContentSize::AccessibilityExtraLarge => "accessibility-extra-large",
+			ContentSize::AccessibilityExtraExtraLarge => "accessibility-extra-extra-large",
+			ContentSize::AccessibilityExtraExtraExtraLarge => "accessibility-extra-extra-extra-large",
+		}
+	}
+
+	/// Whether this is one of the larger accessibility sizes
+	pub fn is_accessibility(&self) -> bool {
+		*self >= ContentSize::AccessibilityMedium
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum IncreaseContrast {
+	Enabled,
+	Disabled,
+}
+
+impl IncreaseContrast {
+	const ALL: [Self; 2] = [Self::Enabled, Self::Disabled];
+
+	fn as_arg(&self) -> &'static str {
+		match self {
+			IncreaseContrast::Enabled => "enabled",
+			IncreaseContrast::Disabled => "disabled",
+		}
+	}
+}
+
+impl XcRunSimctlInstance<'_> {
+	fn ui_command(&self, device_name: &DeviceName, option: &str) -> bossy::Command {
+		self
+			.bossy_command()
+			.with_arg("ui")
+			.with_arg(device_name.to_string())
+			.with_arg(option)
+	}
+
+	/// Gets whether the simulator is in light or dark mode
+	#[instrument(skip_all, ret, fields(%device_name))]
+	pub fn ui_appearance(&self, device_name: &DeviceName) -> Result<UiOutput> {
+		UiOutput::from_bossy_result(
+			self
+				.ui_command(device_name, "appearance")
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Switches the simulator to light or dark mode
+	#[instrument(skip_all, ret, fields(%device_name, ?appearance))]
+	pub fn set_ui_appearance(
+		&self,
+		device_name: &DeviceName,
+		appearance: UiAppearance,
+	) -> Result<UiOutput> {
+		UiOutput::from_bossy_result(
+			self
+				.ui_command(device_name, "appearance")
+				.with_arg(appearance.as_arg())
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Gets the simulator's preferred Dynamic Type size
+	#[instrument(skip_all, ret, fields(%device_name))]
+	pub fn ui_content_size(&self, device_name: &DeviceName) -> Result<UiOutput> {
+		UiOutput::from_bossy_result(
+			self
+				.ui_command(device_name, "content_size")
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Sets the simulator's preferred Dynamic Type size
+	#[instrument(skip_all, ret, fields(%device_name, ?content_size))]
+	pub fn set_ui_content_size(
+		&self,
+		device_name: &DeviceName,
+		content_size: ContentSize,
+	) -> Result<UiOutput> {
+		UiOutput::from_bossy_result(
+			self
+				.ui_command(device_name, "content_size")
+				.with_arg(content_size.as_arg())
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Gets whether the Increase Contrast accessibility setting is enabled
+	#[instrument(skip_all, ret, fields(%device_name))]
+	pub fn ui_increase_contrast(&self, device_name: &DeviceName) -> Result<UiOutput> {
+		UiOutput::from_bossy_result(
+			self
+				.ui_command(device_name, "increase_contrast")
+				.run_and_wait_for_output(),
+		)
+	}
+
+	/// Enables or disables the Increase Contrast accessibility setting
+	#[instrument(skip_all, ret, fields(%device_name, ?increase_contrast))]
+	pub fn set_ui_increase_contrast(
+		&self,
+		device_name: &DeviceName,
+		increase_contrast: IncreaseContrast,
+	) -> Result<UiOutput> {
+		UiOutput::from_bossy_result(
+			self
+				.ui_command(device_name, "increase_contrast")
+				.with_arg(increase_contrast.as_arg())
+				.run_and_wait_for_output(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn ui_args() {
+		let xcrun = super::super::echo_xcrun();
+		let simctl = xcrun.simctl();
+		let device_name = DeviceName::from_str("iPhone 15 Pro").unwrap();
+		let stdout = |output: UiOutput| match output {
+			UiOutput::SuccessUnImplemented { stdout } => stdout,
+			output => panic!("Unexpected output {:?}", output),
+		};
+
+		assert_eq!(
+			stdout(simctl.ui_appearance(&device_name).unwrap()),
+			"simctl ui iPhone 15 Pro appearance\n"
+		);
+		assert_eq!(
+			stdout(
+				simctl
+					.set_ui_appearance(&device_name, UiAppearance::Dark)
+					.unwrap()
+			),
+			"simctl ui iPhone 15 Pro appearance dark\n"
+		);
+		assert_eq!(
+			stdout(simctl.ui_content_size(&device_name).unwrap()),
+			"simctl ui iPhone 15 Pro content_size\n"
+		);
+		assert_eq!(
+			stdout(
+				simctl
+					.set_ui_content_size(&device_name, ContentSize::AccessibilityExtraLarge)
+					.unwrap()
+			),
+			"simctl ui iPhone 15 Pro content_size accessibility-extra-large\n"
+		);
+		assert_eq!(
+			stdout(simctl.ui_increase_contrast(&device_name).unwrap()),
+			"simctl ui iPhone 15 Pro increase_contrast\n"
+		);
+		assert_eq!(
+			stdout(
+				simctl
+					.set_ui_increase_contrast(&device_name, IncreaseContrast::Enabled)
+					.unwrap()
+			),
+			"simctl ui iPhone 15 Pro increase_contrast enabled\n"
+		);
+	}
+}
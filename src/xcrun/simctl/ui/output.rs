@@ -0,0 +1,150 @@
+use crate::prelude::*;
+
+use super::{ContentSize, IncreaseContrast, UiAppearance};
+
+/// Output of the `simctl ui` getters and setters.
+/// Setters print nothing, so parse as [UiOutput::SuccessUnImplemented]
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[must_use = include_doc!(must_use_cmd_output)]
+pub enum UiOutput {
+	Appearance {
+		appearance: UiAppearance,
+	},
+
+	ContentSize {
+		content_size: ContentSize,
+	},
+
+	IncreaseContrast {
+		increase_contrast: IncreaseContrast,
+	},
+
+	/// The device doesn't support the setting, or `simctl` couldn't read it:
+	/// ```text
+	/// unsupported
+	/// ```
+	Unsupported {
+		value: String,
+	},
+
+	#[doc = include_doc!(cmd_success)]
+	SuccessUnImplemented {
+		stdout: String,
+	},
+
+	#[doc = include_doc!(cmd_error)]
+	ErrorUnImplemented {
+		stderr: String,
+	},
+}
+
+impl UiOutput {
+	pub fn appearance(&self) -> Option<UiAppearance> {
+		match self {
+			UiOutput::Appearance { appearance } => Some(*appearance),
+			_ => None,
+		}
+	}
+
+	pub fn content_size(&self) -> Option<ContentSize> {
+		match self {
+			UiOutput::ContentSize { content_size } => Some(*content_size),
+			_ => None,
+		}
+	}
+
+	pub fn increase_contrast(&self) -> Option<IncreaseContrast> {
+		match self {
+			UiOutput::IncreaseContrast { increase_contrast } => Some(*increase_contrast),
+			_ => None,
+		}
+	}
+}
+
+impl CommandNomParsable for UiOutput {
+	fn success_unimplemented(stdout: String) -> Self {
+		Self::SuccessUnImplemented { stdout }
+	}
+
+	fn error_unimplemented(stderr: String) -> Self {
+		Self::ErrorUnImplemented { stderr }
+	}
+
+	fn success_nom_from_str(input: &str) -> IResult<&str, Self> {
+		alt((
+			parse_value,
+			map(rest, |s: &str| Self::success_unimplemented(s.to_owned())),
+		))(input)
+	}
+}
+
+/// Parses a single value printed by a `simctl ui` getter, e.g. `dark` or `extra-large`
+fn parse_value(input: &str) -> IResult<&str, UiOutput> {
+	map_opt(
+		ws(take_till1(|c: char| c.is_whitespace())),
+		|value: &str| {
+			if let Some(appearance) = UiAppearance::ALL.into_iter().find(|v| v.as_arg() == value) {
+				Some(UiOutput::Appearance { appearance })
+			} else if let Some(content_size) = ContentSize::ALL.into_iter().find(|v| v.as_arg() == value)
+			{
+				Some(UiOutput::ContentSize { content_size })
+			} else if let Some(increase_contrast) = IncreaseContrast::ALL
+				.into_iter()
+				.find(|v| v.as_arg() == value)
+			{
+				Some(UiOutput::IncreaseContrast { increase_contrast })
+			} else if value == "unsupported" || value == "unknown" {
+				Some(UiOutput::Unsupported {
+					value: value.to_owned(),
+				})
+			} else {
+				None
+			}
+		},
+	)(input)
+}
+
+impl PublicCommandOutput for UiOutput {
+	type PrimarySuccess = ();
+
+	fn success(&self) -> Result<&Self::PrimarySuccess> {
+		match self {
+			UiOutput::ErrorUnImplemented { .. } => Err(Error::output_errored(self)),
+			UiOutput::Unsupported { .. } => Err(Error::output_errored_with_hint(
+				self,
+				"This setting is only supported by booted iOS 13+ simulators",
+			)),
+			_ => Ok(&()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_values() {
+		assert_eq!(
+			UiOutput::success_from_str("dark\n").appearance(),
+			Some(UiAppearance::Dark)
+		);
+		assert_eq!(
+			UiOutput::success_from_str("accessibility-extra-large\n").content_size(),
+			Some(ContentSize::AccessibilityExtraLarge)
+		);
+		assert_eq!(
+			UiOutput::success_from_str("enabled\n").increase_contrast(),
+			Some(IncreaseContrast::Enabled)
+		);
+		assert!(matches!(
+			UiOutput::success_from_str("unsupported\n"),
+			UiOutput::Unsupported { .. }
+		));
+		assert!(matches!(
+			UiOutput::success_from_str(""),
+			UiOutput::SuccessUnImplemented { .. }
+		));
+	}
+}
@@ -0,0 +1,251 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::prelude::*;
+use crate::shared::identifiers::RuntimeIdentifier;
+
+use super::list::{ListDevice, ListJson, State};
+use super::XcRunSimctlInstance;
+
+/// A simulator as seen in a [ListJson] snapshot
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WatchedDevice {
+	pub udid: String,
+	pub name: DeviceName,
+	pub runtime: RuntimeIdentifier,
+	pub state: State,
+	pub is_available: bool,
+}
+
+impl WatchedDevice {
+	fn new(runtime: &RuntimeIdentifier, device: &ListDevice) -> Self {
+		Self {
+			udid: device.udid.clone(),
+			name: device.name.clone(),
+			runtime: runtime.clone(),
+			state: device.state.clone(),
+			is_available: device.is_available,
+		}
+	}
+}
+
+/// A change between two successive [ListJson] snapshots, see [diff_snapshots]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum SimulatorEvent {
+	Created {
+		device: WatchedDevice,
+	},
+	Deleted {
+		device: WatchedDevice,
+	},
+	Booted {
+		device: WatchedDevice,
+	},
+	ShutDown {
+		device: WatchedDevice,
+	},
+	/// E.g. because its runtime was deleted
+	BecameUnavailable {
+		device: WatchedDevice,
+		reason: Option<String>,
+	},
+	BecameAvailable {
+		device: WatchedDevice,
+	},
+	/// Any other state change, e.g. to [State::Booting]
+	StateChanged {
+		device: WatchedDevice,
+		from: State,
+	},
+}
+
+fn watched_devices(list: &ListJson) -> HashMap<&str, (WatchedDevice, Option<&str>)> {
+	list
+		.devices_with_runtime()
+		.map(|(runtime, device)| {
+			(
+				device.udid.as_str(),
+				(
+					WatchedDevice::new(runtime, device),
+					device.availability_error.as_deref(),
+				),
+			)
+		})
+		.collect()
+}
+
+/// Compares two snapshots by device UDID.
+/// Events are ordered by UDID so that the output is deterministic
+pub fn diff_snapshots(old: &ListJson, new: &ListJson) -> Vec<SimulatorEvent> {
+	let old = watched_devices(old);
+	let new = watched_devices(new);
+	let mut udids = old.keys().chain(new.keys()).copied().collect::<Vec<_>>();
+	udids.sort_unstable();
+	udids.dedup();
+
+	let mut events = Vec::new();
+	for udid in udids {
+		match (old.get(udid), new.get(udid)) {
+			(None, Some((device, _))) => events.push(SimulatorEvent::Created {
+				device: device.clone(),
+			}),
+			(Some((device, _)), None) => events.push(SimulatorEvent::Deleted {
+				device: device.clone(),
+			}),
+			(Some((old, _)), Some((device, availability_error))) => {
+				if old.is_available && !device.is_available {
+					events.push(SimulatorEvent::BecameUnavailable {
+						device: device.clone(),
+						reason: availability_error.map(str::to_owned),
+					});
+				} else if !old.is_available && device.is_available {
+					events.push(SimulatorEvent::BecameAvailable {
+						device: device.clone(),
+					});
+				}
+				if old.state != device.state {
+					let device = device.clone();
+					events.push(match device.state {
+						State::Booted => SimulatorEvent::Booted { device },
+						State::Shutdown => SimulatorEvent::ShutDown { device },
+						_ => SimulatorEvent::StateChanged {
+							device,
+							from: old.state.clone(),
+						},
+					});
+				}
+			}
+			(None, None) => unreachable!("UDID came from one of the snapshots"),
+		}
+	}
+	events
+}
+
+/// Polls `simctl list` and yields the [SimulatorEvent]s between each snapshot.
+/// Never ends on its own, see [XcRunSimctlInstance::watch]
+#[derive(Debug)]
+pub struct SimulatorWatcher<'instance, 'src> {
+	simctl: &'instance XcRunSimctlInstance<'src>,
+	interval: Duration,
+	/// Whether `simctl list` has been run yet, successfully or not
+	polled: bool,
+	last: Option<ListJson>,
+	pending: VecDeque<SimulatorEvent>,
+}
+
+impl SimulatorWatcher<'_, '_> {
+	fn snapshot(&self) -> Result<ListJson> {
+		match self.simctl.list()? {
+			super::list::ListOutput::SuccessJson(list) => Ok(list),
+			output => Err(Error::output_errored(output)),
+		}
+	}
+}
+
+impl Iterator for SimulatorWatcher<'_, '_> {
+	type Item = Result<SimulatorEvent>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some(event) = self.pending.pop_front() {
+				return Some(Ok(event));
+			}
+			// failed polls wait too, so a persistent failure doesn't spin
+			if self.polled {
+				std::thread::sleep(self.interval);
+			}
+			self.polled = true;
+			let snapshot = match self.snapshot() {
+				Ok(snapshot) => snapshot,
+				Err(err) => return Some(Err(err)),
+			};
+			if let Some(last) = &self.last {
+				self.pending.extend(diff_snapshots(last, &snapshot));
+			}
+			self.last = Some(snapshot);
+		}
+	}
+}
+
+impl<'src> XcRunSimctlInstance<'src> {
+	/// Watches for simulators being created, deleted, booted, shut down
+	/// or becoming unavailable, by polling `simctl list` every `interval`.
+	/// The first snapshot is taken as the baseline, so existing simulators aren't reported.
+	/// An [Err] from one poll doesn't end the watcher, the next call polls again
+	pub fn watch(&self, interval: Duration) -> SimulatorWatcher<'_, 'src> {
+		SimulatorWatcher {
+			simctl: self,
+			interval,
+			polled: false,
+			last: None,
+			pending: VecDeque::new(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn list_json() -> serde_json::Value {
+		serde_json::from_str(include_str!(concat!(
+			env!("CARGO_MANIFEST_DIR"),
+			"/tests/simctl-list-full.json"
+		)))
+		.unwrap()
+	}
+
+	#[test]
+	fn diffs_snapshots() {
+		let old = list_json();
+		let mut new = old.clone();
+		let devices = new["devices"]["com.apple.CoreSimulator.SimRuntime.iOS-17-5"]
+			.as_array_mut()
+			.unwrap();
+		let deleted = devices.remove(0);
+		devices[0]["state"] = "Booted".into();
+		devices[1]["state"] = "Booting".into();
+		devices[2]["isAvailable"] = false.into();
+		let mut created = deleted.clone();
+		created["udid"] = "00000000-0000-0000-0000-000000000000".into();
+		devices.push(created);
+
+		let old: ListJson = serde_json::from_value(old).unwrap();
+		let new: ListJson = serde_json::from_value(new).unwrap();
+		let events = diff_snapshots(&old, &new);
+		assert_eq!(events.len(), 5, "{:#?}", events);
+		assert!(matches!(events[0], SimulatorEvent::Created { .. }));
+		assert!(events.iter().any(
+			|event| matches!(event, SimulatorEvent::Deleted { device } if device.udid == deleted["udid"])
+		));
+		assert!(events
+			.iter()
+			.any(|event| matches!(event, SimulatorEvent::Booted { .. })));
+		assert!(events.iter().any(|event| matches!(
+			event,
+			SimulatorEvent::StateChanged {
+				from: State::Shutdown,
+				..
+			}
+		)));
+		assert!(events
+			.iter()
+			.any(|event| matches!(event, SimulatorEvent::BecameUnavailable { .. })));
+
+		assert!(diff_snapshots(&old, &old).is_empty());
+	}
+
+	#[test]
+	fn keeps_polling_after_errors() {
+		// `echo` doesn't print `simctl list` JSON, so every poll fails
+		let xcrun = super::super::echo_xcrun();
+		let simctl = xcrun.simctl();
+		let interval = Duration::from_millis(20);
+		let start = std::time::Instant::now();
+		let results: Vec<_> = simctl.watch(interval).take(3).collect();
+		assert!(results.iter().all(Result::is_err));
+		assert!(start.elapsed() >= interval * 2);
+	}
+}